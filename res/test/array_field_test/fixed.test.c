@@ -0,0 +1,5 @@
+#include "../api.h"
+
+API struct Fixed {
+    int values[4];
+};
@@ -0,0 +1,7 @@
+#include "../api.h"
+
+API struct Message {
+    char name[32];
+    unsigned int length;
+    unsigned char data[];
+};
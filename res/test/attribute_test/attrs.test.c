@@ -0,0 +1,21 @@
+#include "../api.h"
+
+API __attribute__((deprecated("Use new_api instead"))) void old_api(void);
+
+API _Noreturn void fatal(const char *message);
+
+API __attribute__((warn_unused_result, nonnull(1, 2))) int copy(char *dst, const char *src);
+
+API __attribute__((returns_nonnull, malloc, alloc_size(1))) void *allocate(unsigned long size);
+
+API __attribute__((format(printf, 1, 2))) int print(const char *format, ...);
+
+API __attribute__((pure)) int length(const char *s);
+
+API __attribute__((const)) int square(int x);
+
+API __attribute__((deprecated)) int old_var;
+
+struct __attribute__((deprecated("Use Point instead"))) OldPoint {
+    int x;
+};
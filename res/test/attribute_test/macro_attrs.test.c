@@ -0,0 +1,17 @@
+#include "../api.h"
+
+#define MYLIB_WARN_UNUSED __attribute__((warn_unused_result))
+#define MYLIB_NONNULL(...) __attribute__((nonnull(__VA_ARGS__)))
+#define MYLIB_CHECKED MYLIB_WARN_UNUSED MYLIB_NONNULL(1)
+
+API MYLIB_WARN_UNUSED MYLIB_NONNULL(1, 2) int macro_copy(char *dst, const char *src);
+
+API MYLIB_CHECKED int macro_checked(const char *s);
+
+API __attribute__((noreturn)) void gnu_fatal(const char *message);
+
+API void run_handler(void (*handler)(void) __attribute__((noreturn)));
+
+typedef int noreturn_count_t;
+
+API noreturn_count_t count_noreturns(void);
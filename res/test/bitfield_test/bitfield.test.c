@@ -0,0 +1,16 @@
+#include "../api.h"
+
+API struct Flags {
+    unsigned a : 3;
+    unsigned b : 5;
+    unsigned : 0;
+    unsigned c : 2;
+    int regular;
+    unsigned d : 1;
+};
+
+API struct CharFlags {
+    char a : 3;
+    char : 0;
+    char b : 3;
+};
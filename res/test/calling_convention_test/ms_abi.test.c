@@ -0,0 +1,3 @@
+#include "../api.h"
+
+API void __attribute__((ms_abi)) ms_abi_fn(void);
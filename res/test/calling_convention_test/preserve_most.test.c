@@ -0,0 +1,3 @@
+#include "../api.h"
+
+API void __attribute__((preserve_most)) preserve_most_fn(void);
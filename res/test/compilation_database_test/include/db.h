@@ -0,0 +1,3 @@
+#include "../../api.h"
+
+API int db_header_fn(void);
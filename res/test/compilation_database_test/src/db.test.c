@@ -0,0 +1,3 @@
+#include "db.h"
+
+API DB_RETURN_T db_fn(void);
@@ -0,0 +1 @@
+#define shared_name 7
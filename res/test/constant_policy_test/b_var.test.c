@@ -0,0 +1,3 @@
+#include "../api.h"
+
+API int shared_name;
@@ -0,0 +1,22 @@
+#include "../api.h"
+
+enum Color {
+    RED
+};
+
+typedef unsigned long size_alias_t;
+
+API struct Types {
+    unsigned char u8;
+    long long i64;
+    char c;
+    float f;
+    const char *const_str;
+    char *const const_ptr;
+    volatile int *restrict restrict_ptr;
+    int fixed[4];
+    int (*callback)(void *, double);
+    struct Types *next;
+    enum Color color;
+    size_alias_t alias;
+};
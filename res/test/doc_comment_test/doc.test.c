@@ -0,0 +1,33 @@
+#include "../api.h"
+
+/**
+ * Sums two integers.
+ *
+ * The result wraps around on overflow.
+ *
+ * @param a The first term.
+ * @param[in] b The second term.
+ * @return The sum of @p a and @p b.
+ * @deprecated Use checked_sum instead.
+ * @see checked_sum
+ */
+API int sum(int a, int b);
+
+/// A point on a plane.
+struct Point {
+    int x; ///< The abscissa.
+    int y; ///< The ordinate.
+};
+
+/** @brief Primary colors.
+ *
+ * Used to draw points.
+ */
+enum Color {
+    /// The red color.
+    Red,
+    Green,
+};
+
+/// The maximal number of points.
+#define MAX_POINTS 64
@@ -0,0 +1,21 @@
+#define MYLIB_EXPORT
+#define MYLIB_API __attribute__((visibility("default")))
+
+int first_unmarked(void);
+
+MYLIB_EXPORT int first_marked(void);
+
+int second_unmarked(void);
+
+MYLIB_EXPORT int marked_var;
+
+int unmarked_var;
+
+int MYLIB_EXPORT inner_marked(void);
+
+MYLIB_EXPORT
+int next_line_marked(void);
+
+MYLIB_API int api_fn(void);
+
+int last_unmarked(void);
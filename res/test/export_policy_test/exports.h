@@ -0,0 +1,8 @@
+#ifndef _EXPORTS_H_
+#define _EXPORTS_H_
+
+#define POLICY_CONST 1
+
+int header_fn(void);
+
+#endif // _EXPORTS_H_
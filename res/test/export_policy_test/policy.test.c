@@ -0,0 +1,17 @@
+#include "exports.h"
+
+#define MARKED
+
+#define LOCAL_CONST 2
+
+__attribute__((annotate("export"))) int annotated_fn(void);
+
+MARKED int marked_fn(void);
+
+int plain_fn(void);
+
+static int internal_fn(void) {
+    return 0;
+}
+
+int plain_var;
@@ -0,0 +1,3 @@
+#include "../api.h"
+
+API BASE_T a_fn(CALLBACK_T x);
@@ -0,0 +1,3 @@
+#include "../../api.h"
+
+API BASE_T b_fn(LEGACY_T x);
@@ -0,0 +1,7 @@
+#include "../api.h"
+
+typedef void handler_fn(int);
+typedef int log_fn(const char *format, ...);
+
+API handler_fn on_signal;
+API log_fn log_message;
@@ -0,0 +1 @@
+#define FOO_NEG (long)-2
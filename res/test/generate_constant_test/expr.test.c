@@ -0,0 +1,2 @@
+#define FOO_BIT 4
+#define FOO_FLAGS ((unsigned)1 << FOO_BIT)
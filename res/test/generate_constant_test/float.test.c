@@ -0,0 +1 @@
+#define FOO_PI 3.5
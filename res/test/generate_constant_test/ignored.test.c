@@ -0,0 +1,5 @@
+#include "../api.h"
+
+#define FOO_FN(x) (x + 1)
+#define FOO_EMPTY
+#define FOO_TYPE int
@@ -0,0 +1 @@
+#define FOO_MAX 64
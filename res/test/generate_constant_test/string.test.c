@@ -0,0 +1 @@
+#define FOO_NAME "x"
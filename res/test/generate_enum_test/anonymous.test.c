@@ -0,0 +1,4 @@
+enum {
+    FLAG_A = 1,
+    FLAG_B = 2
+};
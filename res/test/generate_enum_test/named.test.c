@@ -0,0 +1,5 @@
+enum Color {
+    RED,
+    GREEN = 5,
+    BLUE = -1
+};
@@ -0,0 +1,3 @@
+enum Big {
+    BIG_MAX = 0xFFFFFFFFu
+};
@@ -0,0 +1,3 @@
+#include "../api.h"
+
+API int fn3(const char *fmt, ...);
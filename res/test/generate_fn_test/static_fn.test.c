@@ -0,0 +1,7 @@
+#include "../api.h"
+
+API static void static_fn(void) {}
+
+API static inline int static_inline_fn(int x) {
+    return x;
+}
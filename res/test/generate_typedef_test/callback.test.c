@@ -0,0 +1 @@
+typedef int (*cb_t)(void *);
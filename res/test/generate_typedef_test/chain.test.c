@@ -0,0 +1,2 @@
+typedef unsigned long base_t;
+typedef base_t chain_t;
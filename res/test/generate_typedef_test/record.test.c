@@ -0,0 +1 @@
+typedef struct foo foo_t;
@@ -0,0 +1,5 @@
+#include "../api.h"
+
+typedef unsigned int handle_t;
+
+API void close_handle(handle_t handle);
@@ -0,0 +1,6 @@
+#include "../api.h"
+
+API union Value {
+    int i;
+    double d;
+};
\ No newline at end of file
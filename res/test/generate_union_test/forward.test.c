@@ -0,0 +1,3 @@
+#include "../api.h"
+
+API union Forward;
\ No newline at end of file
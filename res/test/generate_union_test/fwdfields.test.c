@@ -0,0 +1,9 @@
+#include "../api.h"
+
+/*NOT API*/ union FwdFields;
+
+API union FwdFields {
+    char *some;
+};
+
+API union FwdFields;
\ No newline at end of file
@@ -0,0 +1,3 @@
+#include "../api.h"
+
+API static const char *static_test_var = "Not linkable";
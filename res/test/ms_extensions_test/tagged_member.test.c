@@ -0,0 +1,14 @@
+#include "../api.h"
+
+struct Tagged {
+    int t;
+};
+
+API struct MsOuter {
+    int a;
+    struct Tagged;
+    union {
+        char c;
+        short s;
+    };
+};
@@ -0,0 +1,15 @@
+#include "../api.h"
+
+API struct Outer {
+    struct Inner {
+        int x;
+    } in;
+    union {
+        int i;
+        float f;
+    } u;
+    union {
+        char c;
+        short s;
+    };
+};
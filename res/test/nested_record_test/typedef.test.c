@@ -0,0 +1,3 @@
+typedef struct {
+    int x;
+} point_t;
@@ -0,0 +1,3 @@
+#include "shared.h"
+
+API void b_fn(void);
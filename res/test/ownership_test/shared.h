@@ -0,0 +1,8 @@
+#ifndef _SHARED_H_
+#define _SHARED_H_
+
+#include "../api.h"
+
+API void shared_fn(void);
+
+#endif // _SHARED_H_
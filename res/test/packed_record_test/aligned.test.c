@@ -0,0 +1,5 @@
+#include "../api.h"
+
+API struct __attribute__((aligned(16))) Aligned {
+    int a;
+};
@@ -0,0 +1,6 @@
+#include "../api.h"
+
+API struct AlignedField {
+    char a;
+    _Alignas(8) int b;
+};
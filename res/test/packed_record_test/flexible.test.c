@@ -0,0 +1,6 @@
+#include "../api.h"
+
+API struct Flexible {
+    char len;
+    int data[];
+};
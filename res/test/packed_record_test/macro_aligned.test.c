@@ -0,0 +1,7 @@
+#include "../api.h"
+
+#define MYLIB_ALIGNED(n) __attribute__((aligned(n)))
+
+API struct MYLIB_ALIGNED(32) MacroAligned {
+    int a;
+};
@@ -0,0 +1,6 @@
+#include "../api.h"
+
+API struct __attribute__((packed)) Packed {
+    char a;
+    int b;
+};
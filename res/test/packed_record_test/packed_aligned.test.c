@@ -0,0 +1,6 @@
+#include "../api.h"
+
+API struct __attribute__((packed, aligned(4))) PackedAligned {
+    char c;
+    int i;
+};
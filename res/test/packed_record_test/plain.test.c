@@ -0,0 +1,6 @@
+#include "../api.h"
+
+API struct Plain {
+    char a;
+    int b;
+};
@@ -0,0 +1,8 @@
+#include "../api.h"
+
+#pragma pack(push, 2)
+API struct Pragma {
+    char a;
+    int b;
+};
+#pragma pack(pop)
@@ -0,0 +1,3 @@
+#include "../../api.h"
+
+API void nested_fn(void);
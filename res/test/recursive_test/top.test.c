@@ -0,0 +1,3 @@
+#include "../api.h"
+
+API void top_fn(void);
@@ -0,0 +1,7 @@
+#include "../api.h"
+
+API struct Layout {
+    char a;
+    int b;
+    short c;
+};
@@ -0,0 +1,5 @@
+#include "../api.h"
+
+API int exported_fn(void);
+
+int plain_fn(void);
@@ -0,0 +1,16 @@
+#include "../api.h"
+
+API const int const_var = 1;
+
+API const char const_chars[] = "const";
+
+API int mutable_var = 2;
+
+API _Thread_local int tls_var;
+
+API extern int extern_var;
+
+API int tentative_var;
+
+API extern int defined_later_var;
+API int defined_later_var = 3;
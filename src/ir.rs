@@ -2,54 +2,511 @@
 
 extern crate proc_macro2;
 
-use std::path::Path;
+use std::ffi::CString;
+use std::path::{Path, PathBuf};
 
-#[derive(Debug)]
-pub enum Symbol<'a> {
-    Function(FnSignature<'a>),
-    Struct(Struct<'a>),
-    Variable(Variable<'a>),
+#[derive(Debug, Clone, PartialEq)]
+pub enum Symbol {
+    Function(FnSignature),
+    Struct(Struct),
+    Union(Union),
+    Variable(Variable),
+    Enum(Enum),
+    Typedef(Typedef),
+    Constant(Constant),
 }
 
-#[derive(Debug)]
-pub struct FnSignature<'a> {
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CType {
+    display_name: String,
+    kind: TypeKind,
+    is_const: bool,
+    is_volatile: bool,
+    is_restrict: bool,
+    canonical: Option<Box<CType>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum TypeKind {
+    Void,
+    Bool,
+    /// Plain `char`, whose signedness depends on the target.
+    Char {
+        signed: bool,
+    },
+    /// Any other integer type. The width is in bits.
+    Integer {
+        width: usize,
+        signed: bool,
+    },
+    /// Floating point type. The width is in bits.
+    Float {
+        width: usize,
+    },
+    Pointer(Box<CType>),
+    ConstantArray {
+        element: Box<CType>,
+        length: usize,
+    },
+    IncompleteArray(Box<CType>),
+    Function(FnType),
+    Record {
+        kind: RecordKind,
+        name: String,
+    },
+    Enum(String),
+    Typedef {
+        name: String,
+        underlying: Box<CType>,
+    },
+    /// A type which has no representation in the IR (vectors, complex numbers, etc.).
+    Unsupported,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RecordKind {
+    Struct,
+    Union,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct FnType {
+    result_type: Box<CType>,
+    parameter_types: Vec<CType>,
+    is_variadic: bool,
+    has_prototype: bool,
+    calling_convention: CallingConvention,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum CallingConvention {
+    C,
+    Stdcall,
+    Fastcall,
+    Vectorcall,
+    Thiscall,
+    SysV64,
+    Win64,
+    Aapcs,
+    AapcsVfp,
+    /// A convention which has no Rust ABI counterpart, named as clang reports it.
+    Unsupported(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FnSignature {
     name: String,
-    ctype: clang::Type<'a>,
-    parameters: Vec<FnParameter<'a>>,
+    ctype: CType,
+    result_type: CType,
+    parameters: Vec<FnParameter>,
+    attributes: Vec<Attribute>,
+    comment: Option<Comment>,
+    location: Location,
 }
 
-#[derive(Debug)]
-pub struct Struct<'a> {
+#[derive(Debug, Clone, PartialEq)]
+pub struct Struct {
     name: String,
-    ctype: clang::Type<'a>,
-    fields: Vec<StructField<'a>>,
+    parent: Option<String>,
+    ctype: CType,
+    layout: Option<Layout>,
+    fields: Vec<StructField>,
+    bitfield_units: Vec<BitfieldUnit>,
+    attributes: Vec<Attribute>,
+    comment: Option<Comment>,
+    location: Location,
 }
 
-#[derive(Debug)]
-pub struct Variable<'a> {
+/// Size and alignment of a complete record, in bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Layout {
+    size: usize,
+    alignment: usize,
+    packing: Option<usize>,
+    explicit_alignment: Option<usize>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct StructField {
+    name: String,
+    ctype: CType,
+    bit_offset: Option<usize>,
+    bit_width: Option<usize>,
+    alignment: Option<usize>,
+    comment: Option<Comment>,
+}
+
+/// Byte range of a record which stores a run of adjacent bitfields.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BitfieldUnit {
+    offset: usize,
+    size: usize,
+    field_indices: Vec<usize>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Variable {
+    name: String,
+    ctype: CType,
+    is_thread_local: bool,
+    definition: DefinitionKind,
+    attributes: Vec<Attribute>,
+    comment: Option<Comment>,
+    location: Location,
+}
+
+/// Tells whether a variable is defined in the translation unit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DefinitionKind {
+    /// The variable is only declared `extern` and is defined elsewhere.
+    Declaration,
+
+    /// Declaration without an initializer, e.g. `int x;`,
+    /// which becomes a definition unless the variable is defined elsewhere.
+    Tentative,
+
+    Definition,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FnParameter {
+    name: String,
+    ctype: CType,
+    attributes: Vec<Attribute>,
+    comment: Option<Comment>,
+    param_comment: Option<ParamComment>,
+    location: Location,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Enum {
+    name: Option<String>,
+    ctype: CType,
+    underlying_type: CType,
+    constants: Vec<EnumConstant>,
+    comment: Option<Comment>,
+    location: Location,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct EnumConstant {
+    name: String,
+    value: EnumValue,
+    comment: Option<Comment>,
+}
+
+/// Value of an enumerator, interpreted according to the signedness of the underlying type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EnumValue {
+    Signed(i64),
+    Unsigned(u64),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Typedef {
+    name: String,
+    underlying_type: CType,
+    comment: Option<Comment>,
+    location: Location,
+}
+
+/// Object-like macro whose expansion evaluates to a literal value.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Constant {
     name: String,
-    ctype: clang::Type<'a>,
+    ctype: CType,
+    value: ConstantValue,
+    comment: Option<Comment>,
+    location: Location,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConstantValue {
+    Signed(i64),
+    Unsigned(u64),
+    Float(f64),
+    Char(u8),
+    String(CString),
 }
 
-pub type StructField<'a> = Variable<'a>;
-pub type FnParameter<'a> = Variable<'a>;
+/// Position of a declaration in a source file. Lines and columns start at 1.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SourceLocation {
+    file: PathBuf,
+    line: u32,
+    column: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Location {
+    declaration: SourceLocation,
+    definition: Option<SourceLocation>,
+}
+
+/// C attribute of a function, variable or record declaration.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Attribute {
+    /// `deprecated`, with an optional message.
+    Deprecated(Option<String>),
+    NoReturn,
+    WarnUnusedResult,
+    /// `nonnull` with 1-based parameter indices.
+    /// No indices mean that all pointer parameters are non-null.
+    NonNull(Vec<usize>),
+    ReturnsNonNull,
+    /// `format(archetype, string_index, first_to_check)`, e.g. `format(printf, 1, 2)`.
+    Format {
+        archetype: String,
+        string_index: usize,
+        first_to_check: usize,
+    },
+    Malloc,
+    Pure,
+    Const,
+    /// `alloc_size` with 1-based parameter indices.
+    AllocSize(Vec<usize>),
+}
+
+/// Documentation comment with its Doxygen commands parsed.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Comment {
+    raw: String,
+    brief: Option<String>,
+    details: Vec<String>,
+    params: Vec<ParamComment>,
+    returns: Option<String>,
+    deprecated: Option<String>,
+    see: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ParamComment {
+    name: String,
+    description: String,
+}
+
+pub type Union = Struct;
 
 #[derive(Debug)]
 pub struct TranslationUnit {
     name: String,
+    path: PathBuf,
+    relative_path: PathBuf,
     tokens: proc_macro2::TokenStream,
 }
 
-impl<'a> FnSignature<'a> {
+impl Symbol {
+    pub fn location(&self) -> &Location {
+        match self {
+            Symbol::Function(signature) => signature.location(),
+            Symbol::Struct(decl) | Symbol::Union(decl) => decl.location(),
+            Symbol::Variable(var) => var.location(),
+            Symbol::Enum(decl) => decl.location(),
+            Symbol::Typedef(decl) => decl.location(),
+            Symbol::Constant(constant) => constant.location(),
+        }
+    }
+}
+
+impl CType {
+    pub fn new(ctype: clang::Type) -> CType {
+        CType {
+            display_name: ctype.get_display_name(),
+            kind: TypeKind::new(ctype),
+            is_const: ctype.is_const_qualified(),
+            is_volatile: ctype.is_volatile_qualified(),
+            is_restrict: ctype.is_restrict_qualified(),
+            canonical: Some(ctype.get_canonical_type())
+                .filter(|canonical| *canonical != ctype)
+                .map(|canonical| Box::new(CType::new(canonical))),
+        }
+    }
+
+    pub fn display_name(&self) -> &str {
+        self.display_name.as_str()
+    }
+
+    pub fn kind(&self) -> &TypeKind {
+        &self.kind
+    }
+
+    pub fn is_const(&self) -> bool {
+        self.is_const
+    }
+
+    pub fn is_volatile(&self) -> bool {
+        self.is_volatile
+    }
+
+    pub fn is_restrict(&self) -> bool {
+        self.is_restrict
+    }
+
+    /// Returns the type with all typedefs resolved, or the type itself
+    /// if it is spelled without typedefs.
+    pub fn canonical(&self) -> &CType {
+        self.canonical.as_deref().unwrap_or(self)
+    }
+}
+
+impl TypeKind {
+    fn new(ctype: clang::Type) -> TypeKind {
+        let width = || ctype.get_sizeof().map(|size| size * 8).unwrap_or_default();
+        let boxed = |ctype: Option<clang::Type>| Box::new(CType::new(ctype.unwrap()));
+
+        match ctype.get_kind() {
+            clang::TypeKind::Void => TypeKind::Void,
+            clang::TypeKind::Bool => TypeKind::Bool,
+            clang::TypeKind::CharS => TypeKind::Char { signed: true },
+            clang::TypeKind::CharU => TypeKind::Char { signed: false },
+            clang::TypeKind::SChar
+            | clang::TypeKind::UChar
+            | clang::TypeKind::WChar
+            | clang::TypeKind::Char16
+            | clang::TypeKind::Char32
+            | clang::TypeKind::Short
+            | clang::TypeKind::UShort
+            | clang::TypeKind::Int
+            | clang::TypeKind::UInt
+            | clang::TypeKind::Long
+            | clang::TypeKind::ULong
+            | clang::TypeKind::LongLong
+            | clang::TypeKind::ULongLong
+            | clang::TypeKind::Int128
+            | clang::TypeKind::UInt128 => TypeKind::Integer {
+                width: width(),
+                signed: ctype.is_signed_integer(),
+            },
+            clang::TypeKind::Half
+            | clang::TypeKind::Float16
+            | clang::TypeKind::Float
+            | clang::TypeKind::Double
+            | clang::TypeKind::LongDouble
+            | clang::TypeKind::Float128 => TypeKind::Float { width: width() },
+            clang::TypeKind::Pointer => TypeKind::Pointer(boxed(ctype.get_pointee_type())),
+            clang::TypeKind::ConstantArray => TypeKind::ConstantArray {
+                element: boxed(ctype.get_element_type()),
+                length: ctype.get_size().unwrap(),
+            },
+            clang::TypeKind::IncompleteArray => {
+                TypeKind::IncompleteArray(boxed(ctype.get_element_type()))
+            }
+            clang::TypeKind::FunctionPrototype | clang::TypeKind::FunctionNoPrototype => {
+                TypeKind::Function(FnType::new(ctype))
+            }
+            clang::TypeKind::Record => {
+                let decl = ctype.get_declaration().unwrap();
+                let kind = match decl.get_kind() {
+                    clang::EntityKind::UnionDecl => RecordKind::Union,
+                    _ => RecordKind::Struct,
+                };
+
+                TypeKind::Record {
+                    kind,
+                    name: crate::record_name(&decl).unwrap_or_else(|| ctype.get_display_name()),
+                }
+            }
+            clang::TypeKind::Enum => {
+                let decl = ctype.get_declaration().unwrap();
+                TypeKind::Enum(decl.get_name().unwrap_or_else(|| ctype.get_display_name()))
+            }
+            clang::TypeKind::Typedef => {
+                let decl = ctype.get_declaration().unwrap();
+
+                TypeKind::Typedef {
+                    name: decl.get_name().unwrap(),
+                    underlying: boxed(decl.get_typedef_underlying_type()),
+                }
+            }
+            clang::TypeKind::Elaborated => TypeKind::new(ctype.get_elaborated_type().unwrap()),
+            clang::TypeKind::Attributed => TypeKind::new(ctype.get_modified_type().unwrap()),
+            clang::TypeKind::Unexposed => {
+                let canonical = ctype.get_canonical_type();
+                if canonical.get_kind() == clang::TypeKind::Unexposed {
+                    TypeKind::Unsupported
+                } else {
+                    TypeKind::new(canonical)
+                }
+            }
+            _ => TypeKind::Unsupported,
+        }
+    }
+}
+
+impl FnType {
+    fn new(ctype: clang::Type) -> FnType {
+        FnType {
+            result_type: Box::new(CType::new(ctype.get_result_type().unwrap())),
+            parameter_types: ctype
+                .get_argument_types()
+                .unwrap_or_default()
+                .into_iter()
+                .map(CType::new)
+                .collect(),
+            is_variadic: ctype.is_variadic(),
+            has_prototype: ctype.get_kind() == clang::TypeKind::FunctionPrototype,
+            calling_convention: CallingConvention::new(ctype.get_calling_convention().unwrap()),
+        }
+    }
+
+    pub fn result_type(&self) -> &CType {
+        &self.result_type
+    }
+
+    pub fn parameter_types(&self) -> &Vec<CType> {
+        &self.parameter_types
+    }
+
+    pub fn is_variadic(&self) -> bool {
+        self.is_variadic
+    }
+
+    /// Returns `false` for K&R style declarations like `void fn()`,
+    /// which accept unspecified arguments rather than none.
+    pub fn has_prototype(&self) -> bool {
+        self.has_prototype
+    }
+
+    pub fn calling_convention(&self) -> &CallingConvention {
+        &self.calling_convention
+    }
+}
+
+impl CallingConvention {
+    fn new(calling_convention: clang::CallingConvention) -> CallingConvention {
+        match calling_convention {
+            clang::CallingConvention::Cdecl => CallingConvention::C,
+            clang::CallingConvention::Stdcall => CallingConvention::Stdcall,
+            clang::CallingConvention::Fastcall => CallingConvention::Fastcall,
+            clang::CallingConvention::Vectorcall => CallingConvention::Vectorcall,
+            clang::CallingConvention::Thiscall => CallingConvention::Thiscall,
+            clang::CallingConvention::SysV64 => CallingConvention::SysV64,
+            clang::CallingConvention::Win64 => CallingConvention::Win64,
+            clang::CallingConvention::Aapcs => CallingConvention::Aapcs,
+            clang::CallingConvention::AapcsVfp => CallingConvention::AapcsVfp,
+            other => CallingConvention::Unsupported(format!("{:?}", other)),
+        }
+    }
+}
+
+impl FnSignature {
     pub fn new(
         name: String,
-        ctype: clang::Type<'a>,
-        parameters: Vec<FnParameter<'a>>,
-    ) -> FnSignature<'a> {
+        ctype: CType,
+        result_type: CType,
+        parameters: Vec<FnParameter>,
+        attributes: Vec<Attribute>,
+        comment: Option<Comment>,
+        location: Location,
+    ) -> FnSignature {
         FnSignature {
             name,
             ctype,
+            result_type,
             parameters,
+            attributes,
+            comment,
+            location,
         }
     }
 
@@ -57,25 +514,78 @@ impl<'a> FnSignature<'a> {
         self.name.as_str()
     }
 
-    pub fn ctype(&self) -> &clang::Type<'a> {
+    pub fn location(&self) -> &Location {
+        &self.location
+    }
+
+    pub fn comment(&self) -> Option<&Comment> {
+        self.comment.as_ref()
+    }
+
+    pub fn attributes(&self) -> &Vec<Attribute> {
+        &self.attributes
+    }
+
+    pub fn ctype(&self) -> &CType {
         &self.ctype
     }
 
-    pub fn parameters(&self) -> &Vec<FnParameter<'a>> {
+    pub fn parameters(&self) -> &Vec<FnParameter> {
         &self.parameters
     }
 
-    pub fn result_type(&self) -> clang::Type<'a> {
-        self.ctype().get_result_type().unwrap()
+    pub fn result_type(&self) -> &CType {
+        &self.result_type
+    }
+
+    pub fn is_variadic(&self) -> bool {
+        self.fn_type().is_some_and(FnType::is_variadic)
+    }
+
+    pub fn has_prototype(&self) -> bool {
+        self.fn_type().is_some_and(FnType::has_prototype)
+    }
+
+    pub fn calling_convention(&self) -> &CallingConvention {
+        self.fn_type().unwrap().calling_convention()
+    }
+
+    /// Looks through typedefs, e.g. of `typedef void handler_fn(int); handler_fn on_signal;`.
+    fn fn_type(&self) -> Option<&FnType> {
+        match self.ctype.canonical().kind() {
+            TypeKind::Function(fn_type) => Some(fn_type),
+            _ => None,
+        }
     }
 }
 
-impl<'a> Struct<'a> {
-    pub fn new(name: String, ctype: clang::Type<'a>, fields: Vec<StructField<'a>>) -> Struct<'a> {
+impl Struct {
+    /// Unnamed zero-width bitfields among the fields, like `int : 0;`,
+    /// end the current bitfield unit and are not kept as fields.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        name: String,
+        parent: Option<String>,
+        ctype: CType,
+        layout: Option<Layout>,
+        fields: Vec<StructField>,
+        attributes: Vec<Attribute>,
+        comment: Option<Comment>,
+        location: Location,
+    ) -> Struct {
         Struct {
             name,
+            parent,
             ctype,
-            fields,
+            layout,
+            bitfield_units: BitfieldUnit::group(&fields),
+            fields: fields
+                .into_iter()
+                .filter(|field| !field.is_zero_width())
+                .collect(),
+            attributes,
+            comment,
+            location,
         }
     }
 
@@ -83,32 +593,682 @@ impl<'a> Struct<'a> {
         self.name.as_str()
     }
 
-    pub fn ctype(&self) -> &clang::Type<'a> {
+    pub fn location(&self) -> &Location {
+        &self.location
+    }
+
+    pub fn comment(&self) -> Option<&Comment> {
+        self.comment.as_ref()
+    }
+
+    pub fn attributes(&self) -> &Vec<Attribute> {
+        &self.attributes
+    }
+
+    /// Returns the name of the enclosing record for nested records.
+    pub fn parent(&self) -> Option<&str> {
+        self.parent.as_deref()
+    }
+
+    pub fn ctype(&self) -> &CType {
         &self.ctype
     }
 
-    pub fn fields(&self) -> &Vec<StructField<'a>> {
+    /// Returns `None` if the record is only forward declared in the unit.
+    pub fn layout(&self) -> Option<&Layout> {
+        self.layout.as_ref()
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.layout.is_some()
+    }
+
+    pub fn fields(&self) -> &Vec<StructField> {
         &self.fields
     }
+
+    pub fn bitfield_units(&self) -> &Vec<BitfieldUnit> {
+        &self.bitfield_units
+    }
+
+    /// Returns the trailing array field without a size, like `data` in
+    /// `struct msg { size_t len; uint8_t data[]; }`.
+    /// Zero-length trailing arrays (a GNU extension) are treated the same way.
+    pub fn flexible_array_member(&self) -> Option<&StructField> {
+        self.fields
+            .last()
+            .filter(|field| match field.ctype().canonical().kind() {
+                TypeKind::IncompleteArray(_) => true,
+                TypeKind::ConstantArray { length, .. } => *length == 0,
+                _ => false,
+            })
+    }
+
+    /// Returns `true` if the struct ends with a flexible array member,
+    /// so its instances are larger than its layout size.
+    pub fn is_dynamically_sized(&self) -> bool {
+        self.flexible_array_member().is_some()
+    }
+}
+
+impl BitfieldUnit {
+    /// Groups bitfields which are declared one after another and whose bits
+    /// share or touch the same bytes. Bitfields separated by a regular field
+    /// or by a zero-width bitfield get separate units, since a zero-width
+    /// bitfield ends the allocation unit (C11 6.7.2.1p11).
+    /// The zero-width bitfields are not counted in the field indices.
+    fn group(fields: &[StructField]) -> Vec<BitfieldUnit> {
+        let mut units: Vec<BitfieldUnit> = vec![];
+        let mut unit_end: usize = 0;
+        let mut is_in_unit = false;
+
+        let mut index = 0;
+
+        for field in fields {
+            let (bit_offset, bit_width) = match (field.bit_offset, field.bit_width) {
+                _ if field.is_zero_width() => {
+                    is_in_unit = false;
+                    continue;
+                }
+                (Some(bit_offset), Some(bit_width)) => (bit_offset, bit_width),
+                _ => {
+                    is_in_unit = false;
+                    index += 1;
+                    continue;
+                }
+            };
+
+            if !is_in_unit || bit_offset > unit_end.div_ceil(8) * 8 {
+                units.push(BitfieldUnit {
+                    offset: bit_offset / 8,
+                    size: 0,
+                    field_indices: vec![],
+                });
+                unit_end = bit_offset;
+            }
+
+            let unit = units.last_mut().unwrap();
+            unit_end = unit_end.max(bit_offset + bit_width);
+            unit.size = unit_end.div_ceil(8) - unit.offset;
+            unit.field_indices.push(index);
+
+            is_in_unit = true;
+            index += 1;
+        }
+
+        units
+    }
+
+    /// Byte offset of the unit within the record.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Size of the unit in bytes.
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Indices of the unit bitfields in `Struct::fields()`.
+    pub fn field_indices(&self) -> &Vec<usize> {
+        &self.field_indices
+    }
+}
+
+impl Layout {
+    pub fn new(
+        size: usize,
+        alignment: usize,
+        packing: Option<usize>,
+        explicit_alignment: Option<usize>,
+    ) -> Layout {
+        Layout {
+            size,
+            alignment,
+            packing,
+            explicit_alignment,
+        }
+    }
+
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    pub fn alignment(&self) -> usize {
+        self.alignment
+    }
+
+    /// Returns the maximum field alignment if the record is packed,
+    /// either by `__attribute__((packed))` or by `#pragma pack`.
+    pub fn packing(&self) -> Option<usize> {
+        self.packing
+    }
+
+    /// Returns the alignment of the record's own `__attribute__((aligned(N)))`.
+    /// Aligned fields raise `alignment` but are reported by the fields.
+    pub fn explicit_alignment(&self) -> Option<usize> {
+        self.explicit_alignment
+    }
+}
+
+impl StructField {
+    pub fn new(
+        name: String,
+        ctype: CType,
+        bit_offset: Option<usize>,
+        bit_width: Option<usize>,
+        alignment: Option<usize>,
+        comment: Option<Comment>,
+    ) -> StructField {
+        StructField {
+            name,
+            ctype,
+            bit_offset,
+            bit_width,
+            alignment,
+            comment,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    pub fn ctype(&self) -> &CType {
+        &self.ctype
+    }
+
+    pub fn offset(&self) -> Option<usize> {
+        self.bit_offset.map(|bit_offset| bit_offset / 8)
+    }
+
+    pub fn bit_offset(&self) -> Option<usize> {
+        self.bit_offset
+    }
+
+    pub fn bit_width(&self) -> Option<usize> {
+        self.bit_width
+    }
+
+    pub fn is_bitfield(&self) -> bool {
+        self.bit_width.is_some()
+    }
+
+    fn is_zero_width(&self) -> bool {
+        self.bit_width == Some(0)
+    }
+
+    /// Returns the alignment set on the field by `aligned` or `_Alignas`.
+    pub fn alignment(&self) -> Option<usize> {
+        self.alignment
+    }
+
+    pub fn comment(&self) -> Option<&Comment> {
+        self.comment.as_ref()
+    }
 }
 
-impl<'a> Variable<'a> {
-    pub fn new(name: String, ctype: clang::Type<'a>) -> Variable<'a> {
-        Variable { name, ctype }
+impl Variable {
+    pub fn new(
+        name: String,
+        ctype: CType,
+        is_thread_local: bool,
+        definition: DefinitionKind,
+        attributes: Vec<Attribute>,
+        comment: Option<Comment>,
+        location: Location,
+    ) -> Variable {
+        Variable {
+            name,
+            ctype,
+            is_thread_local,
+            definition,
+            attributes,
+            comment,
+            location,
+        }
     }
 
     pub fn name(&self) -> &str {
         self.name.as_str()
     }
 
-    pub fn ctype(&self) -> &clang::Type<'a> {
+    pub fn location(&self) -> &Location {
+        &self.location
+    }
+
+    pub fn comment(&self) -> Option<&Comment> {
+        self.comment.as_ref()
+    }
+
+    pub fn attributes(&self) -> &Vec<Attribute> {
+        &self.attributes
+    }
+
+    pub fn ctype(&self) -> &CType {
         &self.ctype
     }
+
+    /// Returns true if the variable can't be modified,
+    /// including an array of const elements.
+    pub fn is_const(&self) -> bool {
+        let mut ctype = self.ctype.canonical();
+
+        loop {
+            if ctype.is_const() {
+                return true;
+            }
+
+            match ctype.kind() {
+                TypeKind::ConstantArray { element, .. } | TypeKind::IncompleteArray(element) => {
+                    ctype = element.canonical()
+                }
+                _ => return false,
+            }
+        }
+    }
+
+    /// Returns true if the variable is `_Thread_local`.
+    pub fn is_thread_local(&self) -> bool {
+        self.is_thread_local
+    }
+
+    pub fn definition(&self) -> DefinitionKind {
+        self.definition
+    }
+
+    /// Returns true if the variable is defined in the translation unit,
+    /// either explicitly or by a tentative definition.
+    pub fn is_defined(&self) -> bool {
+        self.definition != DefinitionKind::Declaration
+    }
+}
+
+impl FnParameter {
+    pub fn new(
+        name: String,
+        ctype: CType,
+        attributes: Vec<Attribute>,
+        comment: Option<Comment>,
+        param_comment: Option<ParamComment>,
+        location: Location,
+    ) -> FnParameter {
+        FnParameter {
+            name,
+            ctype,
+            attributes,
+            comment,
+            param_comment,
+            location,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    pub fn location(&self) -> &Location {
+        &self.location
+    }
+
+    pub fn comment(&self) -> Option<&Comment> {
+        self.comment.as_ref()
+    }
+
+    /// Returns the `@param` command of the function comment documenting the parameter.
+    pub fn param_comment(&self) -> Option<&ParamComment> {
+        self.param_comment.as_ref()
+    }
+
+    pub fn attributes(&self) -> &Vec<Attribute> {
+        &self.attributes
+    }
+
+    pub fn ctype(&self) -> &CType {
+        &self.ctype
+    }
+}
+
+impl Enum {
+    pub fn new(
+        name: Option<String>,
+        ctype: CType,
+        underlying_type: CType,
+        constants: Vec<EnumConstant>,
+        comment: Option<Comment>,
+        location: Location,
+    ) -> Enum {
+        Enum {
+            name,
+            ctype,
+            underlying_type,
+            constants,
+            comment,
+            location,
+        }
+    }
+
+    /// Returns `None` for anonymous enums, which only group constants.
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    pub fn location(&self) -> &Location {
+        &self.location
+    }
+
+    pub fn comment(&self) -> Option<&Comment> {
+        self.comment.as_ref()
+    }
+
+    pub fn ctype(&self) -> &CType {
+        &self.ctype
+    }
+
+    pub fn underlying_type(&self) -> &CType {
+        &self.underlying_type
+    }
+
+    pub fn constants(&self) -> &Vec<EnumConstant> {
+        &self.constants
+    }
+}
+
+impl EnumConstant {
+    pub fn new(name: String, value: EnumValue, comment: Option<Comment>) -> EnumConstant {
+        EnumConstant {
+            name,
+            value,
+            comment,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    pub fn value(&self) -> EnumValue {
+        self.value
+    }
+
+    pub fn comment(&self) -> Option<&Comment> {
+        self.comment.as_ref()
+    }
+}
+
+impl Typedef {
+    pub fn new(
+        name: String,
+        underlying_type: CType,
+        comment: Option<Comment>,
+        location: Location,
+    ) -> Typedef {
+        Typedef {
+            name,
+            underlying_type,
+            comment,
+            location,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    pub fn location(&self) -> &Location {
+        &self.location
+    }
+
+    pub fn comment(&self) -> Option<&Comment> {
+        self.comment.as_ref()
+    }
+
+    pub fn underlying_type(&self) -> &CType {
+        &self.underlying_type
+    }
+}
+
+impl Constant {
+    pub fn new(
+        name: String,
+        ctype: CType,
+        value: ConstantValue,
+        comment: Option<Comment>,
+        location: Location,
+    ) -> Constant {
+        Constant {
+            name,
+            ctype,
+            value,
+            comment,
+            location,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    pub fn location(&self) -> &Location {
+        &self.location
+    }
+
+    pub fn comment(&self) -> Option<&Comment> {
+        self.comment.as_ref()
+    }
+
+    pub fn ctype(&self) -> &CType {
+        &self.ctype
+    }
+
+    pub fn value(&self) -> &ConstantValue {
+        &self.value
+    }
+}
+
+impl SourceLocation {
+    /// Returns `None` if the entity is not located in a file, e.g. a builtin typedef.
+    pub fn new(entity: clang::Entity) -> Option<SourceLocation> {
+        let location = entity.get_location()?.get_file_location();
+
+        Some(SourceLocation {
+            file: location.file?.get_path(),
+            line: location.line,
+            column: location.column,
+        })
+    }
+
+    pub fn file(&self) -> &Path {
+        self.file.as_path()
+    }
+
+    pub fn line(&self) -> u32 {
+        self.line
+    }
+
+    pub fn column(&self) -> u32 {
+        self.column
+    }
+}
+
+impl Location {
+    pub fn new(decl: clang::Entity) -> Option<Location> {
+        let declaration = SourceLocation::new(decl)?;
+        let definition = decl
+            .get_definition()
+            .and_then(SourceLocation::new)
+            .filter(|definition| *definition != declaration);
+
+        Some(Location {
+            declaration,
+            definition,
+        })
+    }
+
+    pub fn declaration(&self) -> &SourceLocation {
+        &self.declaration
+    }
+
+    /// Returns the location of the definition if it differs from the declaration,
+    /// e.g. for a forward declared struct which is defined later.
+    pub fn definition(&self) -> Option<&SourceLocation> {
+        self.definition.as_ref()
+    }
+}
+
+impl Comment {
+    pub fn new(raw: String) -> Comment {
+        let mut comment = Comment {
+            raw,
+            brief: None,
+            details: vec![],
+            params: vec![],
+            returns: None,
+            deprecated: None,
+            see: vec![],
+        };
+
+        let mut text_paragraphs = vec![];
+
+        for (command, text) in comment_paragraphs(&comment.raw) {
+            match command.as_deref() {
+                None => text_paragraphs.push(text),
+                Some("brief") | Some("short") => comment.brief = Some(text),
+                Some("param") => {
+                    // Skip the direction, e.g. `@param[in]`.
+                    let text = match text.strip_prefix('[') {
+                        Some(text) => text.split_once(']').map_or("", |(_, text)| text),
+                        None => text.as_str(),
+                    };
+                    let (name, description) = text.trim().split_once(' ').unwrap_or((text, ""));
+
+                    comment.params.push(ParamComment {
+                        name: String::from(name.trim()),
+                        description: String::from(description.trim()),
+                    });
+                }
+                Some("return") | Some("returns") | Some("result") => comment.returns = Some(text),
+                Some("deprecated") => comment.deprecated = Some(text),
+                Some("see") | Some("sa") => comment.see.push(text),
+                Some(_) => comment.details.push(text),
+            }
+        }
+
+        // Without an explicit `@brief` the first paragraph is the brief description.
+        let mut text_paragraphs = text_paragraphs.into_iter();
+        if comment.brief.is_none() {
+            comment.brief = text_paragraphs.next();
+        }
+        comment.details.splice(0..0, text_paragraphs);
+
+        comment
+    }
+
+    /// Returns the comment as it is written in the source, including the comment markers.
+    pub fn raw(&self) -> &str {
+        self.raw.as_str()
+    }
+
+    pub fn brief(&self) -> Option<&str> {
+        self.brief.as_deref()
+    }
+
+    /// Returns the paragraphs following the brief description.
+    pub fn details(&self) -> &Vec<String> {
+        &self.details
+    }
+
+    pub fn params(&self) -> &Vec<ParamComment> {
+        &self.params
+    }
+
+    pub fn param(&self, name: &str) -> Option<&ParamComment> {
+        self.params.iter().find(|param| param.name() == name)
+    }
+
+    pub fn returns(&self) -> Option<&str> {
+        self.returns.as_deref()
+    }
+
+    /// Returns the `@deprecated` text, which is empty if the command has no text.
+    pub fn deprecated(&self) -> Option<&str> {
+        self.deprecated.as_deref()
+    }
+
+    pub fn see(&self) -> &Vec<String> {
+        &self.see
+    }
+}
+
+impl ParamComment {
+    pub fn name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    pub fn description(&self) -> &str {
+        self.description.as_str()
+    }
+}
+
+/// Splits the comment text into paragraphs, each optionally starting with
+/// a Doxygen command (`@cmd` or `\cmd`). A paragraph ends at an empty line
+/// or at the next command.
+fn comment_paragraphs(raw: &str) -> Vec<(Option<String>, String)> {
+    let mut paragraphs: Vec<(Option<String>, String)> = vec![];
+    let mut is_in_paragraph = false;
+
+    for line in raw.lines() {
+        let mut line = line.trim();
+        for marker in [
+            "/**<", "///<", "//!<", "/*!<", "/**", "/*!", "///", "//!", "/*", "//",
+        ]
+        .iter()
+        {
+            if let Some(rest) = line.strip_prefix(marker) {
+                line = rest;
+                break;
+            }
+        }
+        line = line.trim_end().strip_suffix("*/").unwrap_or(line).trim();
+        line = line.strip_prefix('*').unwrap_or(line).trim();
+
+        if line.is_empty() {
+            is_in_paragraph = false;
+            continue;
+        }
+
+        let command = line
+            .strip_prefix('@')
+            .or_else(|| line.strip_prefix('\\'))
+            .filter(|rest| rest.starts_with(|c: char| c.is_ascii_alphabetic()));
+
+        if let Some(rest) = command {
+            let name_len = rest
+                .find(|c: char| !c.is_ascii_alphanumeric() && c != '_')
+                .unwrap_or(rest.len());
+            let (name, text) = rest.split_at(name_len);
+
+            paragraphs.push((Some(String::from(name)), String::from(text.trim())));
+            is_in_paragraph = true;
+        } else if is_in_paragraph {
+            let (_, text) = paragraphs.last_mut().unwrap();
+            if !text.is_empty() {
+                text.push(' ');
+            }
+            text.push_str(line);
+        } else {
+            paragraphs.push((None, String::from(line)));
+            is_in_paragraph = true;
+        }
+    }
+
+    paragraphs
 }
 
 impl TranslationUnit {
-    pub fn new<T: AsRef<Path>>(
+    pub fn new<T: AsRef<Path>, R: AsRef<Path>>(
         file_name: T,
+        relative_path: R,
         tokens: Vec<proc_macro2::TokenStream>,
     ) -> TranslationUnit {
         let tokens = quote! {
@@ -116,6 +1276,8 @@ impl TranslationUnit {
         };
 
         TranslationUnit {
+            path: file_name.as_ref().to_path_buf(),
+            relative_path: relative_path.as_ref().to_path_buf(),
             name: file_name
                 .as_ref()
                 .file_name()
@@ -132,6 +1294,15 @@ impl TranslationUnit {
         self.name.as_str()
     }
 
+    pub fn path(&self) -> &Path {
+        self.path.as_path()
+    }
+
+    /// Returns the path of the unit relative to the scanned directory.
+    pub fn relative_path(&self) -> &Path {
+        self.relative_path.as_path()
+    }
+
     pub fn tokens(&self) -> &proc_macro2::TokenStream {
         &self.tokens
     }
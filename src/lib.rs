@@ -9,23 +9,115 @@ extern crate proc_macro2;
 #[macro_use]
 extern crate lazy_static;
 
-use std::collections::{HashMap, HashSet};
+extern crate glob;
+
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
-mod ir;
+pub mod ir;
 
 type SymbolName = String;
+type MacroValues = HashMap<SymbolName, (ir::CType, clang::EvaluationResult)>;
 type StructName = String;
 type HasFields = bool;
+type FileArgumentsCallback = Box<dyn Fn(&Path) -> Vec<String> + Send + Sync>;
+
+lazy_static! {
+    static ref CLANG: clang::Clang = clang::Clang::new().expect("Unable to initialize clang");
+}
+
+/// Decides which functions and variables are exported and reach the user generator.
+///
+/// Only symbols with external linkage can be exported regardless of the policy.
+/// Macro constants are limited only by the `DeclaredIn` and `Custom` policies,
+/// since they have neither linkage nor visibility nor attributes.
+#[derive(Default)]
+pub enum ExportPolicy {
+    /// `DllExport` for Windows targets and `DefaultVisibility` for the others.
+    #[default]
+    Platform,
+
+    /// Symbols with the default visibility.
+    DefaultVisibility,
+
+    /// Symbols declared with `__declspec(dllexport)`.
+    DllExport,
+
+    /// All symbols with external linkage.
+    ExternalLinkage,
+
+    /// Symbols declared in one of the headers.
+    /// A header matches if the declaration file path ends with it.
+    DeclaredIn(Vec<PathBuf>),
+
+    /// Symbols annotated with `__attribute__((annotate("...")))`.
+    Annotation(String),
+
+    /// Symbols marked with the macro, which may also expand to nothing.
+    Macro(String),
+
+    Custom(Box<dyn Fn(&ir::Symbol) -> bool + Send + Sync>),
+}
+
+/// Selects the source files found in the scanned directory.
+struct SourceFiles {
+    extensions: Vec<String>,
+    include_globs: Vec<glob::Pattern>,
+    exclude_globs: Vec<glob::Pattern>,
+}
+
+/// Extra arguments of the files matching a glob pattern,
+/// or extra arguments returned by a callback for each file.
+enum FileArguments {
+    Pattern(glob::Pattern, Vec<String>),
+    Callback(FileArgumentsCallback),
+}
+
+/// Macros of the current file, used to read the attributes applied through macros.
+#[derive(Default)]
+struct Macros {
+    definitions: HashMap<SymbolName, MacroDefinition>,
+
+    /// Tokens of the macro invocations by their file and offset.
+    expansions: HashMap<(PathBuf, u32), Vec<String>>,
+}
+
+struct MacroDefinition {
+    /// Parameters of a function-like macro.
+    parameters: Option<Vec<String>>,
+    body: Vec<String>,
+}
 
+/// Generates bindings for the source files, one translation unit per file.
+///
+/// Files are processed in the order of their paths. A symbol declared in several
+/// files, e.g. in a shared header, is owned by the first unit which declares it,
+/// and the later units skip it. The only exception is a record which is
+/// forward declared first: the first unit which defines it generates it again.
+/// The owned symbols are kept between `generate` calls of the same generator.
+///
+/// libclang can't evaluate macros by itself, so a file which defines object-like
+/// macros is parsed a second time to evaluate them as constants. Empty macros,
+/// e.g. include guards, and attribute macros don't cause the second parse.
 #[derive(Default)]
 pub struct Generator {
     symbols: HashSet<SymbolName>,
     structs: HashMap<StructName, HasFields>,
+    source_files: SourceFiles,
     arguments: Vec<String>,
+    file_arguments: Vec<FileArguments>,
+    target: Option<String>,
+    export_policy: ExportPolicy,
+    macros: Macros,
 }
 
+// A generator may be configured on one thread and run on another.
+const _: fn() = || {
+    fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<Generator>();
+};
+
 impl Generator {
     pub fn new() -> Generator {
         Self::default()
@@ -36,15 +128,84 @@ impl Generator {
         Dir: AsRef<Path>,
         Gen: Fn(ir::Symbol) -> Option<proc_macro2::TokenStream>,
     {
-        lazy_static! {
-            static ref CLANG: clang::Clang =
-                clang::Clang::new().expect("Unable to initialize clang");
-        };
-
         let index = clang::Index::new(&CLANG, false, false);
         self.generate_units_helper(dir, &user_gen, &index)
     }
 
+    /// Generates units for the listed files regardless of the source file selection.
+    /// The files are processed in the given order.
+    /// The relative path of each unit is the file path as it is given.
+    pub fn generate_files<File, Gen>(
+        &mut self,
+        files: &[File],
+        user_gen: Gen,
+    ) -> Vec<ir::TranslationUnit>
+    where
+        File: AsRef<Path>,
+        Gen: Fn(ir::Symbol) -> Option<proc_macro2::TokenStream>,
+    {
+        let index = clang::Index::new(&CLANG, false, false);
+
+        files
+            .iter()
+            .map(|file| self.generate_for_file(&index, Path::new(""), file, &[], &user_gen))
+            .collect()
+    }
+
+    /// Generates units for the files of the compilation database
+    /// (`compile_commands.json`) found in the directory.
+    ///
+    /// Each file is parsed with the arguments and in the working directory
+    /// of its compile command. The generator arguments come first and the
+    /// command arguments follow them, so a flag of the command which overrides
+    /// an earlier one, e.g. `-D` of the same macro, wins, while the include
+    /// directories of the generator are searched before the ones of the command.
+    /// If a file has several commands, only the first one is used.
+    /// The files are selected and ordered as the files of a scanned `root`
+    /// directory, and their paths are relative to it. The working directory
+    /// of a command may be a separate build directory, so files outside
+    /// of the root are skipped.
+    pub fn generate_from_compilation_database<Dir, Root, Gen>(
+        &mut self,
+        dir: Dir,
+        root: Root,
+        user_gen: Gen,
+    ) -> Vec<ir::TranslationUnit>
+    where
+        Dir: AsRef<Path>,
+        Root: AsRef<Path>,
+        Gen: Fn(ir::Symbol) -> Option<proc_macro2::TokenStream>,
+    {
+        let root = root.as_ref();
+        let database = clang::CompilationDatabase::from_directory(dir.as_ref())
+            .expect("Unable to load compilation database");
+        let commands = database.get_all_compile_commands();
+
+        // The map keeps the files sorted.
+        let mut files = BTreeMap::new();
+        for command in commands.get_commands() {
+            let file = command.get_directory().join(command.get_filename());
+
+            let is_selected = file
+                .strip_prefix(root)
+                .is_ok_and(|relative_path| self.source_files.is_selected(relative_path));
+            if is_selected {
+                files
+                    .entry(file)
+                    .or_insert_with(|| compile_command_arguments(&command));
+            }
+        }
+
+        let index = clang::Index::new(&CLANG, false, false);
+
+        files
+            .into_iter()
+            .map(|(file, arguments)| {
+                self.generate_for_file(&index, root, file, &arguments, &user_gen)
+            })
+            .collect()
+    }
+
     pub fn generate_units_helper<'a, Dir, Gen>(
         &mut self,
         dir: Dir,
@@ -55,29 +216,50 @@ impl Generator {
         Dir: AsRef<Path>,
         Gen: Fn(ir::Symbol) -> Option<proc_macro2::TokenStream>,
     {
-        let dir = dir.as_ref();
+        let root = dir.as_ref();
 
-        let mut units = vec![];
+        let mut files = vec![];
+        if root.is_dir() {
+            self.source_files.collect(root, root, &mut files);
+        }
 
-        if dir.is_dir() {
-            for entry in fs::read_dir(dir).unwrap() {
-                let entry = entry.unwrap();
-                let path = entry.path();
+        // `read_dir` order depends on the file system,
+        // so the files are sorted to make the symbol ownership reproducible.
+        files.sort();
 
-                if path.is_dir() {
-                    self.generate_units_helper(path, user_gen, index);
-                } else {
-                    let ext = path.extension();
-                    if ext.is_some() && ext.unwrap() == "c" {
-                        let unit = self.generate_for_file(index, path, user_gen);
+        files
+            .into_iter()
+            .map(|file| self.generate_for_file(index, root, file, &[], user_gen))
+            .collect()
+    }
 
-                        units.push(unit);
-                    }
-                }
-            }
-        }
+    /// Sets the extensions of the source files, `c` by default.
+    pub fn extensions<T: AsRef<str>>(&mut self, extensions: &[T]) -> &mut Self {
+        self.source_files.extensions = extensions
+            .iter()
+            .map(|ext| String::from(ext.as_ref()))
+            .collect();
+
+        self
+    }
+
+    /// Adds a glob pattern which source files must match, e.g. `src/**/*.c`.
+    /// The pattern is matched against the path relative to the scanned directory.
+    /// If there are no include patterns, all the files are included.
+    pub fn include_glob<T: AsRef<str>>(&mut self, pattern: T) -> &mut Self {
+        let pattern = glob::Pattern::new(pattern.as_ref()).expect("Invalid include glob pattern");
+        self.source_files.include_globs.push(pattern);
+
+        self
+    }
 
-        units
+    /// Adds a glob pattern of source files to skip, e.g. `tests/**`.
+    /// The pattern is matched against the path relative to the scanned directory.
+    pub fn exclude_glob<T: AsRef<str>>(&mut self, pattern: T) -> &mut Self {
+        let pattern = glob::Pattern::new(pattern.as_ref()).expect("Invalid exclude glob pattern");
+        self.source_files.exclude_globs.push(pattern);
+
+        self
     }
 
     pub fn c_flag<T: Into<String>>(&mut self, flag: T) -> &mut Self {
@@ -138,10 +320,49 @@ impl Generator {
         self
     }
 
+    /// Adds flags for the files matching the glob pattern, e.g. `legacy/**`.
+    /// The pattern is matched against the path relative to the scanned directory.
+    ///
+    /// The file flags follow the generator arguments and the compile command arguments,
+    /// in the order they were added.
+    pub fn file_c_flags<T: AsRef<str>>(&mut self, pattern: &str, flags: &[T]) -> &mut Self {
+        let pattern = glob::Pattern::new(pattern).expect("Invalid file flags glob pattern");
+        let flags = flags.iter().map(|f| String::from(f.as_ref())).collect();
+        self.file_arguments
+            .push(FileArguments::Pattern(pattern, flags));
+
+        self
+    }
+
+    /// Adds a callback returning flags for the path of each file
+    /// relative to the scanned directory.
+    pub fn file_c_flags_callback<F>(&mut self, callback: F) -> &mut Self
+    where
+        F: Fn(&Path) -> Vec<String> + Send + Sync + 'static,
+    {
+        self.file_arguments
+            .push(FileArguments::Callback(Box::new(callback)));
+
+        self
+    }
+
+    /// Sets the target triple to generate for, e.g. `x86_64-pc-windows-msvc`.
+    pub fn target<T: Into<String>>(&mut self, triple: T) -> &mut Self {
+        self.target = Some(triple.into());
+        self
+    }
+
+    pub fn export_policy(&mut self, policy: ExportPolicy) -> &mut Self {
+        self.export_policy = policy;
+        self
+    }
+
     fn generate_for_file<File, Gen>(
         &mut self,
         index: &clang::Index,
+        root: &Path,
         file: File,
+        file_arguments: &[String],
         user_gen: &Gen,
     ) -> ir::TranslationUnit
     where
@@ -151,6 +372,21 @@ impl Generator {
         let file = file.as_ref();
         assert!(file.is_file());
 
+        let relative_path = file.strip_prefix(root).unwrap_or(file);
+
+        let mut file_arguments = file_arguments.to_vec();
+        for extra_arguments in &self.file_arguments {
+            match extra_arguments {
+                FileArguments::Pattern(pattern, arguments) => {
+                    if matches_glob(pattern, relative_path) {
+                        file_arguments.extend_from_slice(arguments);
+                    }
+                }
+                FileArguments::Callback(callback) => file_arguments.extend(callback(relative_path)),
+            }
+        }
+        let file_arguments = file_arguments.as_slice();
+
         let mut tokens = vec![];
         macro_rules! try_add_tokens {
             ($expr:expr) => {
@@ -160,44 +396,152 @@ impl Generator {
             };
         }
 
-        let unit = self.get_translation_unit(index, file);
-
+        let unit = self.get_translation_unit(index, file, file_arguments, &[]);
         let nodes = unit.get_entity().get_children();
-        for node in nodes {
+        self.macros = Macros::new(&nodes);
+
+        let macro_values = self.evaluate_macros(index, file, file_arguments, &unit);
+        let marked = match &self.export_policy {
+            ExportPolicy::Macro(marker) => marked_declarations(&nodes, marker),
+            _ => HashSet::new(),
+        };
+
+        // Builtin declarations, e.g. `__builtin_va_list`, are not located in a file.
+        for node in nodes.into_iter().filter(has_file_location) {
             match node.get_kind() {
                 clang::EntityKind::FunctionDecl => {
-                    try_add_tokens![self.generate_fn(node, user_gen)];
+                    try_add_tokens![self.generate_fn(node, &marked, user_gen)];
                 }
-                clang::EntityKind::StructDecl => {
+                clang::EntityKind::StructDecl | clang::EntityKind::UnionDecl => {
                     try_add_tokens![self.generate_struct(node, user_gen)];
                 }
                 clang::EntityKind::VarDecl => {
-                    try_add_tokens![self.generate_var(node, user_gen)];
+                    try_add_tokens![self.generate_var(node, &marked, user_gen)];
+                }
+                clang::EntityKind::EnumDecl => {
+                    try_add_tokens![self.generate_enum(node, user_gen)];
+                }
+                clang::EntityKind::TypedefDecl => {
+                    try_add_tokens![self.generate_typedef(node, user_gen)];
+                }
+                clang::EntityKind::MacroDefinition => {
+                    try_add_tokens![self.generate_constant(node, &macro_values, user_gen)];
                 }
                 _ => {}
             }
         }
 
-        ir::TranslationUnit::new(file, tokens)
+        ir::TranslationUnit::new(file, relative_path, tokens)
     }
 
     fn get_translation_unit<'a, T: AsRef<Path>>(
         &self,
         index: &'a clang::Index,
         file: T,
+        file_arguments: &[String],
+        unsaved: &[clang::Unsaved],
     ) -> clang::TranslationUnit<'a> {
+        let mut arguments = self.arguments.clone();
+        arguments.extend_from_slice(file_arguments);
+        if let Some(target) = &self.target {
+            arguments.push(String::from("-target"));
+            arguments.push(target.clone());
+        }
+
         index
             .parser(file.as_ref())
             .keep_going(true)
             .skip_function_bodies(true)
-            .arguments(&self.arguments)
+            .detailed_preprocessing_record(true)
+            .arguments(&arguments)
+            .unsaved(unsaved)
             .parse()
             .unwrap()
     }
 
+    /// Evaluates object-like macros of the unit.
+    ///
+    /// libclang can't evaluate a macro by itself, so the file is reparsed with
+    /// a variable initialized by every candidate macro appended to its end,
+    /// and the initializers are evaluated instead.
+    fn evaluate_macros(
+        &self,
+        index: &clang::Index,
+        file: &Path,
+        file_arguments: &[String],
+        unit: &clang::TranslationUnit,
+    ) -> MacroValues {
+        const MACRO_VAR_PREFIX: &str = "__tebindgen_macro_";
+
+        let mut macro_values = HashMap::new();
+
+        let macro_names = unit
+            .get_entity()
+            .get_children()
+            .into_iter()
+            .filter(|node| node.get_kind() == clang::EntityKind::MacroDefinition)
+            .filter(|node| !node.is_builtin_macro() && !node.is_function_like_macro())
+            .filter(|node| !node.is_in_system_header())
+            .filter(has_file_location)
+            .filter_map(|node| node.get_name())
+            .filter(|name| !self.symbols.contains(name))
+            .filter(|name| {
+                self.macros
+                    .definitions
+                    .get(name)
+                    .is_some_and(MacroDefinition::may_be_constant)
+            })
+            .collect::<Vec<_>>();
+
+        // Include guards and markers don't need the second parse.
+        if macro_names.is_empty() {
+            return macro_values;
+        }
+
+        // Clang accepts sources which are not valid UTF-8, e.g. with Latin-1 comments.
+        let mut source = match fs::read(file) {
+            Ok(bytes) => String::from_utf8_lossy(&bytes).into_owned(),
+            Err(_) => return macro_values,
+        };
+        for name in &macro_names {
+            source += &format!(
+                "\nstatic const __typeof__({0}) {1}{0} = {0};",
+                name, MACRO_VAR_PREFIX
+            );
+        }
+
+        let unsaved = [clang::Unsaved::new(file, source)];
+        let eval_unit = self.get_translation_unit(index, file, file_arguments, &unsaved);
+
+        for node in eval_unit.get_entity().get_children() {
+            if node.get_kind() != clang::EntityKind::VarDecl {
+                continue;
+            }
+
+            let var_name = node.get_name().unwrap_or_default();
+            let macro_name = match var_name.strip_prefix(MACRO_VAR_PREFIX) {
+                Some(macro_name) => macro_name,
+                None => continue,
+            };
+
+            let init = match node.get_children().pop() {
+                Some(init) if init.is_expression() => init,
+                _ => continue,
+            };
+
+            if let (Some(init_type), Some(value)) = (init.get_type(), init.evaluate()) {
+                let ctype = ir::CType::new(init_type);
+                macro_values.insert(String::from(macro_name), (ctype, value));
+            }
+        }
+
+        macro_values
+    }
+
     fn generate_fn<Gen>(
         &mut self,
         fn_decl: clang::Entity,
+        marked: &HashSet<SymbolName>,
         user_gen: &Gen,
     ) -> Option<proc_macro2::TokenStream>
     where
@@ -205,28 +549,13 @@ impl Generator {
     {
         let fn_type = fn_decl.get_type().unwrap();
         let fn_name = fn_decl.get_name().unwrap();
+        let fn_comment = comment(&fn_decl);
         let mut parameters = vec![];
 
         if self.symbols.contains(&fn_name) {
             return None;
         }
 
-        #[allow(unused_mut)]
-        let mut is_exported = {
-            #[cfg(target_family = "unix")]
-            {
-                match fn_decl.get_visibility() {
-                    Some(v) => v == clang::Visibility::Default,
-                    None => false,
-                }
-            }
-
-            #[cfg(not(target_family = "unix"))]
-            {
-                false
-            }
-        };
-
         for child in fn_decl.get_children() {
             #[allow(clippy::single_match)]
             match child.get_kind() {
@@ -234,20 +563,54 @@ impl Generator {
                     let prm_type = child.get_type().unwrap();
                     let prm_name = child.get_name().unwrap_or_default();
 
-                    parameters.push(ir::FnParameter::new(prm_name, prm_type));
+                    // Parameters are usually documented by the `@param` commands
+                    // of the function comment.
+                    let param_comment = fn_comment
+                        .as_ref()
+                        .and_then(|comment| comment.param(&prm_name))
+                        .cloned();
+
+                    parameters.push(ir::FnParameter::new(
+                        prm_name,
+                        ir::CType::new(prm_type),
+                        attributes(&child, &self.macros),
+                        comment(&child),
+                        param_comment,
+                        ir::Location::new(child)?,
+                    ));
                 }
-
-                #[cfg(target_family = "windows")]
-                clang::EntityKind::DllExport => is_exported = true,
                 _ => {}
             }
         }
 
-        if is_exported {
-            self.symbols.insert(fn_name.clone());
+        // A function declared through a typedef has no parameter declarations.
+        if parameters.is_empty() {
+            for prm_type in fn_type.get_argument_types().unwrap_or_default() {
+                parameters.push(ir::FnParameter::new(
+                    String::new(),
+                    ir::CType::new(prm_type),
+                    vec![],
+                    None,
+                    None,
+                    ir::Location::new(fn_decl)?,
+                ));
+            }
+        }
+
+        let result_type = ir::CType::new(fn_type.get_result_type().unwrap());
+        let signature = ir::FnSignature::new(
+            fn_name.clone(),
+            ir::CType::new(fn_type),
+            result_type,
+            parameters,
+            attributes(&fn_decl, &self.macros),
+            fn_comment,
+            ir::Location::new(fn_decl)?,
+        );
+        let symbol = ir::Symbol::Function(signature);
 
-            let signature = ir::FnSignature::new(fn_name, fn_type, parameters);
-            let symbol = ir::Symbol::Function(signature);
+        if self.is_exported(&fn_decl, &symbol, marked) {
+            self.symbols.insert(fn_name);
 
             user_gen(symbol)
         } else {
@@ -263,38 +626,116 @@ impl Generator {
     where
         Gen: Fn(ir::Symbol) -> Option<proc_macro2::TokenStream>,
     {
-        let struct_name = struct_decl.get_name().unwrap();
+        let struct_name = record_name(&struct_decl)?;
+        let location = ir::Location::new(struct_decl)?;
         let struct_type = struct_decl.get_type().unwrap();
+        let parent_name = struct_decl
+            .get_semantic_parent()
+            .filter(is_record)
+            .and_then(|parent| record_name(&parent));
         let mut fields = vec![];
+        let mut nested_tokens = vec![];
 
         if *self.structs.get(&struct_name).unwrap_or(&false) {
             return None;
         }
 
         for child in struct_decl.get_children() {
-            #[allow(clippy::single_match)]
             match child.get_kind() {
                 clang::EntityKind::FieldDecl => {
-                    let field =
-                        ir::StructField::new(child.get_name().unwrap(), child.get_type().unwrap());
+                    // Unnamed bitfields only pad the storage and can't be accessed,
+                    // but zero-width ones end the bitfield unit, so `ir::Struct` gets them.
+                    // Anonymous struct and union members get the suffix of the synthesized
+                    // name of their record, e.g. `anon0` for `Parent_anon0`. A tagged
+                    // anonymous member (`-fms-extensions`) is named after its record.
+                    let field_name = match entity_name(&child) {
+                        Some(field_name) => field_name,
+                        None if child.get_bit_field_width() == Some(0) => String::new(),
+                        None if child.is_bit_field() => continue,
+                        None => {
+                            let field_record_name = match child
+                                .get_type()
+                                .and_then(declared_record)
+                                .and_then(|field_record| record_name(&field_record))
+                            {
+                                Some(field_record_name) => field_record_name,
+                                None => continue,
+                            };
+
+                            match field_record_name.strip_prefix(&format!("{}_", struct_name)) {
+                                Some(suffix) => String::from(suffix),
+                                None => field_record_name,
+                            }
+                        }
+                    };
+
+                    let field = ir::StructField::new(
+                        field_name,
+                        ir::CType::new(child.get_type().unwrap()),
+                        child.get_offset_of_field().ok(),
+                        child.get_bit_field_width(),
+                        aligned_attribute(&child, &self.macros),
+                        comment(&child),
+                    );
 
                     fields.push(field);
                 }
+                clang::EntityKind::StructDecl | clang::EntityKind::UnionDecl => {
+                    if let Some(tokens) = self.generate_struct(child, user_gen) {
+                        nested_tokens.push(tokens);
+                    }
+                }
                 _ => {}
             }
         }
 
-        self.structs.insert(struct_name.clone(), !fields.is_empty());
+        let has_fields = fields.iter().any(|field| field.bit_width() != Some(0));
+        self.structs.insert(struct_name.clone(), has_fields);
+
+        let layout = match (struct_type.get_sizeof(), struct_type.get_alignof()) {
+            (Ok(size), Ok(alignment)) => {
+                let (packing, explicit_alignment) =
+                    record_packing(&struct_decl, alignment, &self.macros);
+                Some(ir::Layout::new(
+                    size,
+                    alignment,
+                    packing,
+                    explicit_alignment,
+                ))
+            }
+            _ => None,
+        };
+
+        let struct_obj = ir::Struct::new(
+            struct_name,
+            parent_name,
+            ir::CType::new(struct_type),
+            layout,
+            fields,
+            attributes(&struct_decl, &self.macros),
+            comment(&struct_decl),
+            location,
+        );
+        let symbol = match struct_decl.get_kind() {
+            clang::EntityKind::UnionDecl => ir::Symbol::Union(struct_obj),
+            _ => ir::Symbol::Struct(struct_obj),
+        };
 
-        let struct_obj = ir::Struct::new(struct_name, struct_type, fields);
-        let symbol = ir::Symbol::Struct(struct_obj);
+        nested_tokens.extend(user_gen(symbol));
 
-        user_gen(symbol)
+        if nested_tokens.is_empty() {
+            None
+        } else {
+            Some(quote! {
+                #(#nested_tokens)*
+            })
+        }
     }
 
     fn generate_var<Gen>(
         &mut self,
         var_decl: clang::Entity,
+        marked: &HashSet<SymbolName>,
         user_gen: &Gen,
     ) -> Option<proc_macro2::TokenStream>
     where
@@ -307,86 +748,1052 @@ impl Generator {
             return None;
         }
 
-        let is_exported = {
-            #[cfg(target_family = "unix")]
-            {
-                match var_decl.get_visibility() {
-                    Some(v) => v == clang::Visibility::Default,
-                    None => false,
+        let var = ir::Variable::new(
+            var_name.clone(),
+            ir::CType::new(var_type),
+            var_decl.get_tls_kind().is_some(),
+            definition_kind(&var_decl),
+            attributes(&var_decl, &self.macros),
+            comment(&var_decl),
+            ir::Location::new(var_decl)?,
+        );
+        let symbol = ir::Symbol::Variable(var);
+
+        if self.is_exported(&var_decl, &symbol, marked) {
+            self.symbols.insert(var_name);
+
+            user_gen(symbol)
+        } else {
+            None
+        }
+    }
+
+    fn is_exported(
+        &self,
+        decl: &clang::Entity,
+        symbol: &ir::Symbol,
+        marked: &HashSet<SymbolName>,
+    ) -> bool {
+        if !has_external_linkage(decl) {
+            return false;
+        }
+
+        let is_dll_exported = || {
+            decl.get_children()
+                .into_iter()
+                .any(|child| child.get_kind() == clang::EntityKind::DllExport)
+        };
+        let has_default_visibility = || decl.get_visibility() == Some(clang::Visibility::Default);
+
+        match &self.export_policy {
+            ExportPolicy::Platform => {
+                // The triple is normalized, so MinGW and Cygwin targets are `*-windows-*` too.
+                let target = decl.get_translation_unit().get_target();
+                if target.triple.contains("-windows") {
+                    is_dll_exported()
+                } else {
+                    has_default_visibility()
                 }
             }
+            ExportPolicy::DefaultVisibility => has_default_visibility(),
+            ExportPolicy::DllExport => is_dll_exported(),
+            ExportPolicy::ExternalLinkage => true,
+            ExportPolicy::DeclaredIn(headers) => is_declared_in(symbol, headers),
+            ExportPolicy::Annotation(annotation) => decl.get_children().into_iter().any(|child| {
+                child.get_kind() == clang::EntityKind::AnnotateAttr
+                    && child.get_name().as_ref() == Some(annotation)
+            }),
+            ExportPolicy::Macro(_) => decl.get_name().is_some_and(|name| marked.contains(&name)),
+            ExportPolicy::Custom(is_exported) => is_exported(symbol),
+        }
+    }
 
-            #[cfg(target_family = "windows")]
-            {
-                var_decl
-                    .get_children()
-                    .iter()
-                    .find(|&child| child.get_kind() == clang::EntityKind::DllExport)
-                    .is_some()
+    fn generate_enum<Gen>(
+        &mut self,
+        enum_decl: clang::Entity,
+        user_gen: &Gen,
+    ) -> Option<proc_macro2::TokenStream>
+    where
+        Gen: Fn(ir::Symbol) -> Option<proc_macro2::TokenStream>,
+    {
+        if !enum_decl.is_definition() {
+            return None;
+        }
+
+        let enum_name = entity_name(&enum_decl);
+        let enum_type = enum_decl.get_type().unwrap();
+        let underlying_type = enum_decl.get_enum_underlying_type().unwrap();
+        let is_signed = underlying_type.get_canonical_type().is_signed_integer();
+        let mut constants = vec![];
+
+        for child in enum_decl.get_children() {
+            #[allow(clippy::single_match)]
+            match child.get_kind() {
+                clang::EntityKind::EnumConstantDecl => {
+                    let (signed, unsigned) = child.get_enum_constant_value().unwrap();
+                    let value = if is_signed {
+                        ir::EnumValue::Signed(signed)
+                    } else {
+                        ir::EnumValue::Unsigned(unsigned)
+                    };
+
+                    constants.push(ir::EnumConstant::new(
+                        child.get_name().unwrap(),
+                        value,
+                        comment(&child),
+                    ));
+                }
+                _ => {}
             }
+        }
 
-            #[cfg(all(not(target_family = "unix"), not(target_family = "windows")))]
-            {
-                unimplemented!()
+        // Anonymous enums are identified by their first enumerator,
+        // since enumerators share the ordinary identifier namespace.
+        let symbol_name = match (&enum_name, constants.first()) {
+            (Some(name), _) => format!("enum {}", name),
+            (None, Some(constant)) => format!("enum {{{}}}", constant.name()),
+            (None, None) => return None,
+        };
+
+        let location = ir::Location::new(enum_decl)?;
+        if !self.symbols.insert(symbol_name) {
+            return None;
+        }
+
+        let enum_obj = ir::Enum::new(
+            enum_name,
+            ir::CType::new(enum_type),
+            ir::CType::new(underlying_type),
+            constants,
+            comment(&enum_decl),
+            location,
+        );
+        let symbol = ir::Symbol::Enum(enum_obj);
+
+        user_gen(symbol)
+    }
+
+    fn generate_typedef<Gen>(
+        &mut self,
+        typedef_decl: clang::Entity,
+        user_gen: &Gen,
+    ) -> Option<proc_macro2::TokenStream>
+    where
+        Gen: Fn(ir::Symbol) -> Option<proc_macro2::TokenStream>,
+    {
+        let typedef_name = typedef_decl.get_name().unwrap();
+        let underlying_type = typedef_decl.get_typedef_underlying_type().unwrap();
+        let location = ir::Location::new(typedef_decl)?;
+
+        if !self.symbols.insert(typedef_name.clone()) {
+            return None;
+        }
+
+        let typedef = ir::Typedef::new(
+            typedef_name,
+            ir::CType::new(underlying_type),
+            comment(&typedef_decl),
+            location,
+        );
+        let symbol = ir::Symbol::Typedef(typedef);
+
+        user_gen(symbol)
+    }
+
+    fn generate_constant<Gen>(
+        &mut self,
+        macro_def: clang::Entity,
+        macro_values: &MacroValues,
+        user_gen: &Gen,
+    ) -> Option<proc_macro2::TokenStream>
+    where
+        Gen: Fn(ir::Symbol) -> Option<proc_macro2::TokenStream>,
+    {
+        let macro_name = macro_def.get_name().unwrap();
+        let (ctype, value) = macro_values.get(&macro_name)?;
+
+        if self.symbols.contains(&macro_name) {
+            return None;
+        }
+
+        // A character literal has type `int` in C,
+        // so it can only be told apart by its spelling.
+        let is_char_literal = match macro_def.get_range().unwrap().tokenize().as_slice() {
+            [_, literal] => {
+                literal.get_kind() == clang::token::TokenKind::Literal
+                    && literal.get_spelling().starts_with('\'')
+            }
+            _ => false,
+        };
+
+        let value = match value.clone() {
+            clang::EvaluationResult::SignedInteger(value) if is_char_literal => {
+                ir::ConstantValue::Char(value as u8)
             }
+            clang::EvaluationResult::SignedInteger(value) => ir::ConstantValue::Signed(value),
+            clang::EvaluationResult::UnsignedInteger(value) => ir::ConstantValue::Unsigned(value),
+            clang::EvaluationResult::Float(value) => ir::ConstantValue::Float(value),
+            clang::EvaluationResult::String(value) => ir::ConstantValue::String(value),
+            _ => return None,
         };
 
-        if is_exported {
-            self.symbols.insert(var_name.clone());
+        let constant = ir::Constant::new(
+            macro_name.clone(),
+            ctype.clone(),
+            value,
+            macro_comment(&macro_def),
+            ir::Location::new(macro_def)?,
+        );
+        let symbol = ir::Symbol::Constant(constant);
 
-            let var = ir::Variable::new(var_name, var_type);
-            let symbol = ir::Symbol::Variable(var);
+        if self.is_constant_exported(&symbol) {
+            self.symbols.insert(macro_name);
 
             user_gen(symbol)
         } else {
             None
         }
     }
+
+    /// Macros have neither linkage nor visibility nor attributes,
+    /// so only the header and custom policies limit the constants.
+    fn is_constant_exported(&self, symbol: &ir::Symbol) -> bool {
+        match &self.export_policy {
+            ExportPolicy::DeclaredIn(headers) => is_declared_in(symbol, headers),
+            ExportPolicy::Custom(is_exported) => is_exported(symbol),
+            _ => true,
+        }
+    }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use std::env;
-    use std::path::PathBuf;
+impl SourceFiles {
+    /// Collects the selected files of the directory and its subdirectories.
+    fn collect(&self, root: &Path, dir: &Path, files: &mut Vec<PathBuf>) {
+        for entry in fs::read_dir(dir).unwrap() {
+            let path = entry.unwrap().path();
 
-    macro_rules! check_fn_symbol {
-        ($signature:expr => {
-            name: $fn_name:ident,
-            ctype: $($ctype:tt)+
-        }) => {{
-            let string_signature = String::from(stringify![$($ctype)*]);
-            let open_brace_idx = string_signature.find('(').expect("ctype: '(' expected");
-            let close_brace_idx = string_signature.rfind(')').expect("ctype: ')' expected");
+            if path.is_dir() {
+                self.collect(root, &path, files);
+            } else if self.is_selected(path.strip_prefix(root).unwrap()) {
+                files.push(path);
+            }
+        }
+    }
 
-            let return_type = string_signature[0..open_brace_idx].trim();
-            let params = string_signature[open_brace_idx+1..close_brace_idx].trim();
-            let params: Vec<&str> = if params.chars().all(|c| c.is_whitespace()) {
-                vec![]
-            } else {
-                params.split(',').collect()
-            };
+    fn is_selected(&self, relative_path: &Path) -> bool {
+        let matches = |pattern: &glob::Pattern| matches_glob(pattern, relative_path);
 
-            let mut params_names = vec![];
-            let mut params_types = vec![];
+        let has_extension = relative_path.extension().is_some_and(|ext| {
+            self.extensions
+                .iter()
+                .any(|expected| ext == expected.as_str())
+        });
 
-            params.iter().for_each(|param| {
-                let param: Vec<&str> = param.split(':').collect();
-                params_names.push(param[0].trim());
-                params_types.push(param[1].trim());
-            });
+        has_extension
+            && (self.include_globs.is_empty() || self.include_globs.iter().any(matches))
+            && !self.exclude_globs.iter().any(matches)
+    }
+}
 
-            let mut string_ctype = String::from(return_type);
-            if return_type.chars().last().unwrap().is_alphanumeric() {
-                string_ctype.push(' ');
+/// Matches the path against the pattern, where `*` doesn't match path separators.
+fn matches_glob(pattern: &glob::Pattern, path: &Path) -> bool {
+    let options = glob::MatchOptions {
+        require_literal_separator: true,
+        ..glob::MatchOptions::new()
+    };
+
+    pattern.matches_path_with(path, options)
+}
+
+impl Default for SourceFiles {
+    fn default() -> Self {
+        SourceFiles {
+            extensions: vec![String::from("c")],
+            include_globs: vec![],
+            exclude_globs: vec![],
+        }
+    }
+}
+
+impl Macros {
+    /// Nested macros are expanded up to this depth.
+    const MAX_DEPTH: usize = 16;
+
+    fn new(entities: &[clang::Entity]) -> Macros {
+        let mut macros = Macros::default();
+
+        for entity in entities.iter().filter(|entity| {
+            entity.get_kind() == clang::EntityKind::MacroDefinition && !entity.is_builtin_macro()
+        }) {
+            let tokens = entity_tokens(entity);
+            let (name, rest) = match tokens.split_first() {
+                Some(split) => split,
+                None => continue,
+            };
+
+            let definition = if entity.is_function_like_macro() {
+                let (parameters, body_start) = parenthesized_list(rest, 0);
+                MacroDefinition {
+                    parameters: Some(parameters.into_iter().flatten().collect()),
+                    body: rest[body_start..].to_vec(),
+                }
+            } else {
+                MacroDefinition {
+                    parameters: None,
+                    body: rest.to_vec(),
+                }
+            };
+
+            macros.definitions.insert(name.clone(), definition);
+        }
+
+        for entity in entities
+            .iter()
+            .filter(|entity| entity.get_kind() == clang::EntityKind::MacroExpansion)
+        {
+            let (name, location) = match (entity.get_name(), entity.get_location()) {
+                (Some(name), Some(location)) => (name, location),
+                _ => continue,
+            };
+
+            // Only the invocations written in the files are looked up.
+            let spelling = location.get_spelling_location();
+            let location = location.get_file_location();
+            let file = match &location.file {
+                Some(file) if location == spelling => file.get_path(),
+                _ => continue,
+            };
+
+            let tokens = match macros.definitions.get(&name) {
+                Some(MacroDefinition {
+                    parameters: Some(_),
+                    ..
+                }) => entity_tokens(entity),
+                Some(_) => vec![name],
+                None => continue,
+            };
+
+            macros.expansions.insert((file, location.offset), tokens);
+        }
+
+        macros
+    }
+
+    /// Returns the tokens of the macro invocation which spells the source location.
+    fn invocation(&self, location: clang::source::SourceLocation) -> Option<&Vec<String>> {
+        let location = location.get_expansion_location();
+        let file = location.file?.get_path();
+
+        self.expansions.get(&(file, location.offset))
+    }
+
+    /// Expands the macros of the tokens.
+    ///
+    /// Stringification and token pasting are not supported.
+    fn expand(&self, tokens: &[String], depth: usize) -> Vec<String> {
+        let mut expanded = vec![];
+        let mut i = 0;
+
+        while i < tokens.len() {
+            let definition = self
+                .definitions
+                .get(&tokens[i])
+                .filter(|_| depth < Self::MAX_DEPTH);
+
+            match definition {
+                Some(MacroDefinition {
+                    parameters: None,
+                    body,
+                }) => {
+                    expanded.extend(self.expand(body, depth + 1));
+                    i += 1;
+                }
+                Some(MacroDefinition {
+                    parameters: Some(parameters),
+                    body,
+                }) if tokens.get(i + 1).is_some_and(|token| token == "(") => {
+                    let (arguments, end) = parenthesized_list(tokens, i + 1);
+                    let body = substitute_arguments(parameters, &arguments, body);
+                    expanded.extend(self.expand(&body, depth + 1));
+                    i = end;
+                }
+                _ => {
+                    expanded.push(tokens[i].clone());
+                    i += 1;
+                }
+            }
+        }
+
+        expanded
+    }
+}
+
+impl MacroDefinition {
+    /// Returns false for macros which can't evaluate to a constant,
+    /// e.g. include guards, empty markers and attribute macros.
+    fn may_be_constant(&self) -> bool {
+        self.parameters.is_none()
+            && !self.body.is_empty()
+            && !self.body.iter().any(|token| {
+                ["__attribute__", "__attribute", "__declspec"].contains(&token.as_str())
+            })
+    }
+}
+
+fn substitute_arguments(
+    parameters: &[String],
+    arguments: &[Vec<String>],
+    body: &[String],
+) -> Vec<String> {
+    body.iter()
+        .flat_map(|token| {
+            if token == "__VA_ARGS__" {
+                let start = parameters
+                    .iter()
+                    .position(|parameter| parameter == "...")
+                    .unwrap_or(parameters.len());
+
+                arguments
+                    .get(start..)
+                    .unwrap_or_default()
+                    .join(&String::from(","))
+            } else if let Some(index) = parameters.iter().position(|parameter| parameter == token) {
+                arguments.get(index).cloned().unwrap_or_default()
+            } else {
+                vec![token.clone()]
+            }
+        })
+        .collect()
+}
+
+/// Splits the comma separated list in the parentheses which open at `open`.
+///
+/// Returns the list items and the index following the closing parenthesis.
+fn parenthesized_list(tokens: &[String], open: usize) -> (Vec<Vec<String>>, usize) {
+    let mut items = vec![vec![]];
+    let mut depth = 0;
+
+    for (i, token) in tokens.iter().enumerate().skip(open) {
+        match token.as_str() {
+            "(" => {
+                depth += 1;
+                if depth == 1 {
+                    continue;
+                }
+            }
+            ")" => {
+                depth -= 1;
+                if depth == 0 {
+                    return (items, i + 1);
+                }
+            }
+            "," if depth == 1 => {
+                items.push(vec![]);
+                continue;
+            }
+            _ => {}
+        }
+
+        items.last_mut().unwrap().push(token.clone());
+    }
+
+    (items, tokens.len())
+}
+
+/// Returns the arguments of the compile command which libclang expects.
+///
+/// The compiler, the source file and the output options are removed,
+/// and the working directory of the command is added.
+fn compile_command_arguments(command: &clang::CompileCommand) -> Vec<String> {
+    let directory = command.get_directory();
+    let file = directory.join(command.get_filename());
+
+    let mut arguments = vec![];
+    let mut command_arguments = command.get_arguments().into_iter().skip(1);
+
+    while let Some(argument) = command_arguments.next() {
+        if argument == "-o" {
+            command_arguments.next();
+        } else if argument != "-c"
+            && !argument.starts_with("-o")
+            && directory.join(&argument) != file
+        {
+            arguments.push(argument);
+        }
+    }
+
+    arguments.push(String::from("-working-directory"));
+    arguments.push(directory.to_string_lossy().into_owned());
+
+    arguments
+}
+
+/// Returns the names of the declarations marked with the macro.
+///
+/// The marker is either expanded inside of the declaration,
+/// or right before it if the marker expands to nothing.
+/// libclang lists the macro expansions apart from the declarations,
+/// so they are matched by their locations in the file.
+fn marked_declarations(entities: &[clang::Entity], marker: &str) -> HashSet<SymbolName> {
+    let declarations = entities
+        .iter()
+        .filter(|entity| {
+            !matches!(
+                entity.get_kind(),
+                clang::EntityKind::MacroExpansion
+                    | clang::EntityKind::MacroDefinition
+                    | clang::EntityKind::InclusionDirective
+            )
+        })
+        .filter_map(|decl| Some((decl, file_range(decl)?)))
+        .collect::<Vec<_>>();
+
+    entities
+        .iter()
+        .filter(|entity| {
+            entity.get_kind() == clang::EntityKind::MacroExpansion
+                && entity.get_name().as_deref() == Some(marker)
+        })
+        .filter_map(file_range)
+        .filter_map(|(marker_start, marker_end)| {
+            let in_same_file = declarations
+                .iter()
+                .filter(|(_, (start, _))| start.file == marker_start.file);
+
+            let enclosing = in_same_file.clone().find(|(_, (start, end))| {
+                start.offset <= marker_start.offset && marker_end.offset <= end.offset
+            });
+            let following = || {
+                in_same_file
+                    .filter(|(_, (start, _))| marker_end.offset <= start.offset)
+                    .min_by_key(|(_, (start, _))| start.offset)
+            };
+
+            enclosing.or_else(following)?.0.get_name()
+        })
+        .collect()
+}
+
+/// Returns the start and the end of the entity in its file.
+fn file_range<'tu>(
+    entity: &clang::Entity<'tu>,
+) -> Option<(clang::source::Location<'tu>, clang::source::Location<'tu>)> {
+    let range = entity.get_range()?;
+    let start = range.get_start().get_file_location();
+    let end = range.get_end().get_file_location();
+
+    start.file.map(|_| (start, end))
+}
+
+fn is_declared_in(symbol: &ir::Symbol, headers: &[PathBuf]) -> bool {
+    let file = symbol.location().declaration().file();
+    headers.iter().any(|header| file.ends_with(header))
+}
+
+fn has_file_location(entity: &clang::Entity) -> bool {
+    entity
+        .get_location()
+        .and_then(|location| location.get_file_location().file)
+        .is_some()
+}
+
+/// Returns true if the symbol can be linked against from other modules.
+///
+/// `static` functions and variables have internal linkage
+/// even if their visibility is default.
+fn has_external_linkage(decl: &clang::Entity) -> bool {
+    decl.get_linkage() == Some(clang::Linkage::External)
+}
+
+fn definition_kind(var_decl: &clang::Entity) -> ir::DefinitionKind {
+    if var_decl.is_definition() || var_decl.get_definition().is_some() {
+        ir::DefinitionKind::Definition
+    } else if var_decl.get_storage_class() == Some(clang::StorageClass::Extern) {
+        ir::DefinitionKind::Declaration
+    } else {
+        ir::DefinitionKind::Tentative
+    }
+}
+
+/// Returns the documentation comment attached to the entity.
+fn comment(entity: &clang::Entity) -> Option<ir::Comment> {
+    entity.get_comment().map(ir::Comment::new)
+}
+
+/// Returns the documentation comment preceding the macro definition.
+///
+/// libclang doesn't attach comments to macros,
+/// so the comment is read from the source file.
+fn macro_comment(macro_def: &clang::Entity) -> Option<ir::Comment> {
+    let location = ir::SourceLocation::new(*macro_def)?;
+    let source = fs::read_to_string(location.file()).ok()?;
+    let mut lines = source
+        .lines()
+        .take(location.line() as usize - 1)
+        .collect::<Vec<_>>();
+
+    let mut comment_lines = vec![];
+    if lines.last()?.trim().ends_with("*/") {
+        while let Some(line) = lines.pop() {
+            comment_lines.push(line);
+            if line.contains("/*") {
+                break;
+            }
+        }
+
+        let first_line = comment_lines.last()?.trim();
+        if !first_line.starts_with("/**") && !first_line.starts_with("/*!") {
+            return None;
+        }
+    } else {
+        while let Some(line) = lines.pop() {
+            let trimmed = line.trim();
+            if !trimmed.starts_with("///") && !trimmed.starts_with("//!") {
+                break;
+            }
+            comment_lines.push(line);
+        }
+    }
+
+    if comment_lines.is_empty() {
+        return None;
+    }
+
+    comment_lines.reverse();
+    Some(ir::Comment::new(comment_lines.join("\n")))
+}
+
+/// Returns the name of the entity, or `None` if it is anonymous.
+///
+/// Newer libclang versions spell anonymous declarations as `(unnamed at ...)`
+/// or `(anonymous at ...)` instead of returning an empty name.
+fn entity_name(entity: &clang::Entity) -> Option<String> {
+    entity
+        .get_name()
+        .filter(|name| !name.contains("(unnamed") && !name.contains("(anonymous"))
+}
+
+/// Returns the name of the record, synthesizing one if the record is anonymous.
+///
+/// An anonymous record nested into another one is named after its parent and
+/// the field it declares (`Parent_field`), or, for anonymous members,
+/// after its parent and its position among them (`Parent_anon0`).
+/// A file scope anonymous record is named after the typedef which names it.
+/// Returns `None` for anonymous records which can't be referred to.
+fn record_name(record_decl: &clang::Entity) -> Option<String> {
+    if let Some(name) = entity_name(record_decl) {
+        return Some(name);
+    }
+
+    let parent = record_decl.get_semantic_parent()?;
+    let siblings = parent.get_children();
+
+    let declares_record =
+        |node: &clang::Entity| node.get_type().and_then(declared_record) == Some(*record_decl);
+
+    if !is_record(&parent) {
+        return siblings
+            .iter()
+            .filter(|node| node.get_kind() == clang::EntityKind::TypedefDecl)
+            .find(|node| {
+                node.get_typedef_underlying_type().and_then(declared_record) == Some(*record_decl)
+            })
+            .and_then(|node| node.get_name());
+    }
+
+    let parent_name = record_name(&parent)?;
+    let fields = siblings
+        .iter()
+        .filter(|node| node.get_kind() == clang::EntityKind::FieldDecl)
+        .collect::<Vec<_>>();
+
+    if let Some(field_name) = fields
+        .iter()
+        .find(|field| declares_record(field))
+        .and_then(|field| entity_name(field))
+    {
+        return Some(format!("{}_{}", parent_name, field_name));
+    }
+
+    let anon_index = fields
+        .iter()
+        .filter(|field| entity_name(field).is_none() && !field.is_bit_field())
+        .position(|field| declares_record(field))?;
+
+    Some(format!("{}_anon{}", parent_name, anon_index))
+}
+
+/// Returns the packing and the explicit alignment of the record.
+///
+/// The explicit alignment is read from the `aligned` attribute of the record.
+/// `#pragma pack` leaves no trace in the AST available through libclang,
+/// so its packing is inferred by comparing the record alignment to the
+/// alignment of its fields.
+fn record_packing(
+    record_decl: &clang::Entity,
+    alignment: usize,
+    macros: &Macros,
+) -> (Option<usize>, Option<usize>) {
+    let children = record_decl.get_children();
+    let is_packed = children
+        .iter()
+        .any(|child| child.get_kind() == clang::EntityKind::PackedAttr);
+
+    let field_alignment = children
+        .iter()
+        .filter(|child| child.get_kind() == clang::EntityKind::FieldDecl)
+        .filter_map(|field| {
+            let natural_alignment = natural_alignment(field.get_type()?)?;
+            Some(natural_alignment.max(aligned_attribute(field, macros).unwrap_or(1)))
+        })
+        .max()
+        .unwrap_or(1);
+
+    let packing = if is_packed {
+        Some(1)
+    } else if alignment < field_alignment {
+        Some(alignment)
+    } else {
+        None
+    };
+
+    (packing, aligned_attribute(record_decl, macros))
+}
+
+/// Returns the alignment of the type, looking through arrays,
+/// since the alignment of an incomplete array is unknown.
+fn natural_alignment(ctype: clang::Type) -> Option<usize> {
+    match ctype.get_kind() {
+        clang::TypeKind::ConstantArray
+        | clang::TypeKind::IncompleteArray
+        | clang::TypeKind::VariableArray => natural_alignment(ctype.get_element_type()?),
+        _ => ctype.get_alignof().ok(),
+    }
+}
+
+/// Returns the alignment of the `aligned` attribute or the `_Alignas`
+/// specifier applied to the declaration.
+///
+/// libclang exposes these only as unexposed attributes, so the value is read
+/// from the attribute tokens and must be an integer literal.
+fn aligned_attribute(decl: &clang::Entity, macros: &Macros) -> Option<usize> {
+    decl.get_children()
+        .into_iter()
+        .filter(|child| child.get_kind() == clang::EntityKind::UnexposedAttr)
+        .flat_map(|attr| attribute_tokens(&attr, macros))
+        .find_map(|tokens| match tokens.as_slice() {
+            [name, open, value, ..]
+                if open == "("
+                    && ["aligned", "__aligned__", "_Alignas", "alignas"]
+                        .contains(&name.as_str()) =>
+            {
+                parse_integer_literal(value)
+            }
+            _ => None,
+        })
+}
+
+/// Returns the attributes of the declaration.
+fn attributes(decl: &clang::Entity, macros: &Macros) -> Vec<ir::Attribute> {
+    let mut attributes = vec![];
+    let mut add = |attribute| {
+        if !attributes.contains(&attribute) {
+            attributes.push(attribute);
+        }
+    };
+
+    for child in decl.get_children() {
+        match child.get_kind() {
+            clang::EntityKind::PureAttr => add(ir::Attribute::Pure),
+            clang::EntityKind::ConstAttr => add(ir::Attribute::Const),
+            clang::EntityKind::UnexposedAttr => attribute_tokens(&child, macros)
+                .iter()
+                .filter_map(|tokens| parse_attribute(tokens))
+                .for_each(&mut add),
+            _ => {}
+        }
+    }
+
+    if decl.get_kind() == clang::EntityKind::FunctionDecl && has_gnu_noreturn(decl, macros) {
+        add(ir::Attribute::NoReturn);
+    }
+
+    // Deprecation is also reported by libclang if the attribute tokens can't be read.
+    if decl.get_availability() == clang::Availability::Deprecated
+        && !attributes
+            .iter()
+            .any(|attr| matches!(attr, ir::Attribute::Deprecated(_)))
+    {
+        attributes.push(ir::Attribute::Deprecated(None));
+    }
+
+    attributes
+}
+
+/// Returns true if the function is declared with the GNU `noreturn` attribute.
+///
+/// Clang folds the attribute into the function type, and libclang exposes it
+/// neither as an attribute nor by a type query, so the attribute specifiers
+/// of the declaration are read, skipping its parameters and its body.
+fn has_gnu_noreturn(fn_decl: &clang::Entity, macros: &Macros) -> bool {
+    let tokens = macros.expand(&file_tokens(fn_decl), 0);
+    let mut i = 0;
+
+    while i < tokens.len() {
+        match tokens[i].as_str() {
+            "{" => break,
+            "(" => i = parenthesized_list(&tokens, i).1,
+            token if token.starts_with("__attribute") || token == "__declspec" => {
+                let end = match tokens.get(i + 1) {
+                    Some(open) if open == "(" => parenthesized_list(&tokens, i + 1).1,
+                    _ => i + 1,
+                };
+
+                let is_noreturn =
+                    split_attribute_specifiers(&tokens[i..end])
+                        .iter()
+                        .any(|attribute| {
+                            attribute.len() == 1 && attribute[0].trim_matches('_') == "noreturn"
+                        });
+                if is_noreturn {
+                    return true;
+                }
+
+                i = end;
+            }
+            _ => i += 1,
+        }
+    }
+
+    false
+}
+
+/// Returns the tokens written in the file for the entity.
+///
+/// Unlike `entity_tokens`, the range starts at the macro invocation
+/// if the entity starts with a macro.
+fn file_tokens(entity: &clang::Entity) -> Vec<String> {
+    let (start, end) = match file_range(entity) {
+        Some(range) => range,
+        None => return vec![],
+    };
+    let file = match start.file {
+        Some(file) => file,
+        None => return vec![],
+    };
+
+    clang::source::SourceRange::new(
+        file.get_offset_location(start.offset),
+        file.get_offset_location(end.offset),
+    )
+    .tokenize()
+    .iter()
+    .map(|token| token.get_spelling())
+    .collect()
+}
+
+/// Returns the tokens of each attribute spelled by the attribute entity, e.g. `nonnull ( 1 )`.
+///
+/// The range of an attribute applied through a macro doesn't cover the attribute,
+/// so the macro invocation is expanded instead, and the attribute specifiers
+/// it expands to are split into single attributes.
+fn attribute_tokens(attr: &clang::Entity, macros: &Macros) -> Vec<Vec<String>> {
+    let location = match attr.get_location() {
+        Some(location) => location,
+        None => return vec![],
+    };
+
+    let tokens = if location.get_spelling_location() == location.get_expansion_location() {
+        entity_tokens(attr)
+    } else {
+        match macros.invocation(location) {
+            Some(invocation) => macros.expand(invocation, 0),
+            None => return vec![],
+        }
+    };
+
+    split_attribute_specifiers(&tokens)
+}
+
+/// Splits attribute specifiers like `__attribute__((a, b(1)))` into single attributes.
+///
+/// Tokens without specifiers are a single attribute.
+fn split_attribute_specifiers(tokens: &[String]) -> Vec<Vec<String>> {
+    let is_specifier =
+        |token: &String| ["__attribute__", "__attribute", "__declspec"].contains(&token.as_str());
+    if !tokens.iter().any(is_specifier) {
+        return vec![tokens.to_vec()];
+    }
+
+    let is_open = |index: usize| tokens.get(index).is_some_and(|token| token == "(");
+    let mut attributes = vec![];
+    let mut i = 0;
+
+    while i < tokens.len() {
+        let token = tokens[i].as_str();
+
+        if token.starts_with("__attribute") && is_open(i + 1) && is_open(i + 2) {
+            let (items, end) = parenthesized_list(tokens, i + 2);
+            attributes.extend(items);
+            // Skips the outer closing parenthesis.
+            i = end + 1;
+        } else if token == "__declspec" && is_open(i + 1) {
+            let (items, end) = parenthesized_list(tokens, i + 1);
+            attributes.extend(items);
+            i = end;
+        } else {
+            // Keywords like `_Noreturn` or `_Alignas(8)`.
+            let end = if is_open(i + 1) {
+                parenthesized_list(tokens, i + 1).1
+            } else {
+                i + 1
+            };
+            attributes.push(tokens[i..end].to_vec());
+            i = end;
+        }
+    }
+
+    attributes.retain(|attribute| !attribute.is_empty());
+    attributes
+}
+
+fn entity_tokens(entity: &clang::Entity) -> Vec<String> {
+    match entity.get_range() {
+        Some(range) => range
+            .tokenize()
+            .iter()
+            .map(|token| token.get_spelling())
+            .collect(),
+        None => vec![],
+    }
+}
+
+/// Parses the attribute from its tokens, e.g. `nonnull ( 1 , 2 )`.
+fn parse_attribute(tokens: &[String]) -> Option<ir::Attribute> {
+    let (name, rest) = tokens.split_first()?;
+    let arguments = match rest {
+        [open, arguments @ .., close] if open == "(" && close == ")" => {
+            arguments.split(|token| token == ",").collect::<Vec<_>>()
+        }
+        _ => vec![],
+    };
+    let indices = || {
+        arguments
+            .iter()
+            .filter_map(|argument| parse_integer_literal(&argument.concat()))
+            .collect::<Vec<_>>()
+    };
+
+    let attribute = match name.trim_matches('_') {
+        "deprecated" => {
+            let message = arguments.first().map(|argument| {
+                argument
+                    .iter()
+                    .map(|literal| literal.trim_matches('"'))
+                    .collect::<String>()
+            });
+
+            ir::Attribute::Deprecated(message.filter(|message| !message.is_empty()))
+        }
+        "noreturn" | "Noreturn" => ir::Attribute::NoReturn,
+        "warn_unused_result" | "nodiscard" => ir::Attribute::WarnUnusedResult,
+        "nonnull" => ir::Attribute::NonNull(indices()),
+        "returns_nonnull" => ir::Attribute::ReturnsNonNull,
+        "format" => match arguments.as_slice() {
+            [archetype, string_index, first_to_check] => ir::Attribute::Format {
+                archetype: String::from(archetype.concat().trim_matches('_')),
+                string_index: parse_integer_literal(&string_index.concat())?,
+                first_to_check: parse_integer_literal(&first_to_check.concat())?,
+            },
+            _ => return None,
+        },
+        "malloc" => ir::Attribute::Malloc,
+        "pure" => ir::Attribute::Pure,
+        "const" => ir::Attribute::Const,
+        "alloc_size" => ir::Attribute::AllocSize(indices()),
+        _ => return None,
+    };
+
+    Some(attribute)
+}
+
+fn parse_integer_literal(literal: &str) -> Option<usize> {
+    let literal = literal.trim_end_matches(|c| "uUlL".contains(c));
+
+    if let Some(hex) = literal
+        .strip_prefix("0x")
+        .or_else(|| literal.strip_prefix("0X"))
+    {
+        usize::from_str_radix(hex, 16).ok()
+    } else if literal.len() > 1 && literal.starts_with('0') {
+        usize::from_str_radix(&literal[1..], 8).ok()
+    } else {
+        literal.parse().ok()
+    }
+}
+
+/// Returns the record declaration of the type, looking through arrays.
+fn declared_record(ctype: clang::Type) -> Option<clang::Entity> {
+    match ctype.get_kind() {
+        clang::TypeKind::ConstantArray
+        | clang::TypeKind::IncompleteArray
+        | clang::TypeKind::VariableArray => declared_record(ctype.get_element_type()?),
+        _ => ctype.get_declaration().filter(is_record),
+    }
+}
+
+fn is_record(entity: &clang::Entity) -> bool {
+    matches!(
+        entity.get_kind(),
+        clang::EntityKind::StructDecl | clang::EntityKind::UnionDecl
+    )
+}
+
+#[cfg(test)]
+#[allow(clippy::single_match)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+    use std::env;
+    use std::path::PathBuf;
+
+    macro_rules! check_fn_symbol {
+        ($signature:expr => {
+            name: $fn_name:ident,
+            ctype: $($ctype:tt)+
+        }) => {{
+            let string_signature = String::from(stringify![$($ctype)*]);
+            let open_brace_idx = string_signature.find('(').expect("ctype: '(' expected");
+            let close_brace_idx = string_signature.rfind(')').expect("ctype: ')' expected");
+
+            let return_type = string_signature[0..open_brace_idx].trim();
+            let params = string_signature[open_brace_idx+1..close_brace_idx].trim();
+            let params: Vec<&str> = if params.chars().all(|c| c.is_whitespace()) {
+                vec![]
+            } else {
+                params.split(',').collect()
+            };
+
+            let mut params_names = vec![];
+            let mut params_types = vec![];
+
+            params.iter().for_each(|param| {
+                let param: Vec<&str> = param.split(':').collect();
+                params_names.push(param[0].trim());
+                params_types.push(param[1].trim());
+            });
+
+            let mut string_ctype = String::from(return_type);
+            if return_type.chars().last().unwrap().is_alphanumeric() {
+                string_ctype.push(' ');
             }
             string_ctype.push('(');
             string_ctype.push_str(params_types.join(", ").as_str());
             string_ctype.push(')');
 
             assert_eq!($signature.name(), stringify![$fn_name]);
-            assert_eq!($signature.ctype().get_display_name(), string_ctype);
-            assert_eq!($signature.result_type().get_display_name(), return_type);
+            assert_eq!($signature.ctype().display_name(), string_ctype);
+            assert_eq!($signature.result_type().display_name(), return_type);
 
             if params.is_empty() {
                 assert!($signature.parameters().is_empty());
@@ -395,7 +1802,7 @@ mod tests {
                     .enumerate()
                     .for_each(|(i, param)| {
                         assert_eq!(param.name(), params_names[i]);
-                        assert_eq!(param.ctype().get_display_name(), params_types[i]);
+                        assert_eq!(param.ctype().display_name(), params_types[i]);
                     });
             }
         }};
@@ -404,7 +1811,14 @@ mod tests {
     macro_rules! check_struct {
         ($struc:expr => $struct_name:ident {
             $($fields:tt)*
+        }) => {
+            check_struct![$struc => struct $struct_name { $($fields)* }]
+        };
+
+        ($struc:expr => $keyword:ident $struct_name:ident {
+            $($fields:tt)*
         }) => {{
+            let keyword = stringify![$keyword];
             let struct_name = stringify![$struct_name];
             let string_fields = stringify![$($fields)*];
             let fields: Vec<&str> = if string_fields.chars().all(|c| c.is_whitespace()) {
@@ -423,7 +1837,7 @@ mod tests {
             });
 
             assert_eq!($struc.name(), struct_name);
-            assert_eq!($struc.ctype().get_display_name(), String::from("struct ") + struct_name);
+            assert_eq!($struc.ctype().display_name(), format!("{} {}", keyword, struct_name));
             if fields.is_empty() {
                 assert!($struc.fields().is_empty());
             } else {
@@ -431,7 +1845,7 @@ mod tests {
                     .enumerate()
                     .for_each(|(i, field)| {
                         assert_eq!(field.name(), fields_names[i]);
-                        assert_eq!(field.ctype().get_display_name(), fields_types[i]);
+                        assert_eq!(field.ctype().display_name(), fields_types[i]);
                     });
             }
         }};
@@ -443,7 +1857,7 @@ mod tests {
             let var_type = stringify![$($var_type)+];
 
             assert_eq!($var.name(), var_name);
-            assert_eq!($var.ctype().get_display_name(), var_type);
+            assert_eq!($var.ctype().display_name(), var_type);
         }};
     }
 
@@ -490,18 +1904,94 @@ mod tests {
                 generate_fn_gen(symbol): match symbol {
                     ir::Symbol::Function(signature) => {
                         match signature.name() {
-                            "fn0" => check_fn_symbol![signature => {
-                                name: fn0,
-                                ctype: void()
-                            }],
-                            "fn1" => check_fn_symbol![signature => {
-                                name: fn1,
-                                ctype: int(: char)
-                            }],
+                            "fn0" => {
+                                check_fn_symbol![signature => {
+                                    name: fn0,
+                                    ctype: void()
+                                }];
+                                assert!(!signature.has_prototype());
+                                assert!(!signature.is_variadic());
+                            }
+                            "fn1" => {
+                                check_fn_symbol![signature => {
+                                    name: fn1,
+                                    ctype: int(: char)
+                                }];
+                                assert!(signature.has_prototype());
+                                assert!(!signature.is_variadic());
+                            }
                             "fn2" => check_fn_symbol![signature => {
                                 name: fn2,
                                 ctype: double(arg1: int, arg2: const char *)
                             }],
+                            "fn3" => {
+                                assert_eq!(signature.ctype().display_name(), "int (const char *, ...)");
+                                assert_eq!(signature.parameters().len(), 1);
+                                assert!(signature.has_prototype());
+                                assert!(signature.is_variadic());
+                            }
+                            name => panic!("unexpected function: {}", name),
+                        }
+                    }
+                    _ => {}
+                }
+            },
+        );
+
+        assert_generator_called![units, generate_fn_gen(4)];
+    }
+
+    #[test]
+    fn test_fn_typedef() {
+        let fn_typedef_test_dir = DATA.clone().as_path().join("fn_typedef_test");
+        let signatures = RefCell::new(HashMap::new());
+
+        Generator::new().generate(fn_typedef_test_dir, |symbol| {
+            if let ir::Symbol::Function(signature) = symbol {
+                signatures
+                    .borrow_mut()
+                    .insert(String::from(signature.name()), signature);
+            }
+            None
+        });
+
+        let signatures = signatures.into_inner();
+
+        let on_signal = &signatures["on_signal"];
+        assert_eq!(on_signal.ctype().display_name(), "handler_fn");
+        assert!(on_signal.has_prototype());
+        assert!(!on_signal.is_variadic());
+        assert_eq!(on_signal.result_type().display_name(), "void");
+        assert_eq!(on_signal.parameters().len(), 1);
+        assert_eq!(on_signal.parameters()[0].ctype().display_name(), "int");
+
+        let log_message = &signatures["log_message"];
+        assert!(log_message.has_prototype());
+        assert!(log_message.is_variadic());
+        assert_eq!(log_message.parameters().len(), 1);
+    }
+
+    #[test]
+    #[cfg(all(target_arch = "x86_64", target_family = "unix"))]
+    fn test_calling_convention() {
+        use ir::CallingConvention;
+
+        let calling_convention_test_dir = DATA.clone().as_path().join("calling_convention_test");
+
+        let units = Generator::new().generate(
+            calling_convention_test_dir,
+            test_generator! {
+                calling_convention_gen(symbol): match symbol {
+                    ir::Symbol::Function(signature) => {
+                        let calling_convention = signature.calling_convention().clone();
+
+                        match signature.name() {
+                            "c_fn" => assert_eq!(calling_convention, CallingConvention::C),
+                            "ms_abi_fn" => assert_eq!(calling_convention, CallingConvention::Win64),
+                            "preserve_most_fn" => assert_eq!(
+                                calling_convention,
+                                CallingConvention::Unsupported(String::from("PreserveMost"))
+                            ),
                             _ => {}
                         }
                     }
@@ -510,7 +2000,26 @@ mod tests {
             },
         );
 
-        assert_generator_called![units, generate_fn_gen(3)];
+        assert_generator_called![units, calling_convention_gen(3)];
+
+        // A function declared through a typedef has the typedef type, not a function type.
+        let fn_typedef_test_dir = DATA.clone().as_path().join("fn_typedef_test");
+        let units = Generator::new().generate(
+            fn_typedef_test_dir,
+            test_generator! {
+                fn_typedef_gen(symbol): match symbol {
+                    ir::Symbol::Function(signature) => match signature.name() {
+                        "on_signal" => {
+                            assert_eq!(*signature.calling_convention(), CallingConvention::C)
+                        }
+                        _ => return None,
+                    },
+                    _ => return None,
+                }
+            },
+        );
+
+        assert_generator_called![units, fn_typedef_gen(1)];
     }
 
     #[test]
@@ -550,22 +2059,1223 @@ mod tests {
     }
 
     #[test]
-    fn test_generate_var() {
-        let generate_var_test_dir = DATA.clone().as_path().join("generate_var_test");
+    fn test_struct_layout() {
+        let struct_layout_test_dir = DATA.clone().as_path().join("struct_layout_test");
 
         let units = Generator::new().generate(
-            generate_var_test_dir,
+            struct_layout_test_dir,
             test_generator! {
-                generate_var_gen(symbol): match symbol {
-                    ir::Symbol::Variable(var) => check_var_symbol![var => test_var: const char *],
-                    _ => {}
-                }
-            },
-        );
+                struct_layout_gen(symbol): match symbol {
+                    ir::Symbol::Struct(decl) => {
+                        match decl.name() {
+                            "Layout" => {
+                                let layout = decl.layout().unwrap();
+                                assert_eq!(layout.size(), 12);
+                                assert_eq!(layout.alignment(), 4);
+
+                                let offsets = decl.fields()
+                                    .iter()
+                                    .map(|field| (field.offset(), field.bit_offset()))
+                                    .collect::<Vec<_>>();
+
+                                assert_eq!(offsets, vec![
+                                    (Some(0), Some(0)),
+                                    (Some(4), Some(32)),
+                                    (Some(8), Some(64)),
+                                ]);
+                            }
+                            "Incomplete" => {
+                                assert!(!decl.is_complete());
+                                assert!(decl.layout().is_none());
+                            }
+                            _ => {}
+                        }
+                    }
+                    _ => {}
+                }
+            },
+        );
+
+        assert_generator_called![units, struct_layout_gen(2)];
+    }
+
+    #[test]
+    fn test_bitfields() {
+        let bitfield_test_dir = DATA.clone().as_path().join("bitfield_test");
+
+        let units = Generator::new().generate(
+            bitfield_test_dir,
+            test_generator! {
+                bitfield_gen(symbol): match symbol {
+                    ir::Symbol::Struct(decl) => {
+                        let fields = decl.fields()
+                            .iter()
+                            .map(|field| (field.name(), field.bit_offset().unwrap(), field.bit_width()))
+                            .collect::<Vec<_>>();
+                        let units = decl.bitfield_units()
+                            .iter()
+                            .map(|unit| (unit.offset(), unit.size(), unit.field_indices().clone()))
+                            .collect::<Vec<_>>();
+
+                        if decl.name() == "CharFlags" {
+                            // The zero-width bitfield ends the unit of `a`,
+                            // even though `b` touches its byte.
+                            assert_eq!(fields, vec![("a", 0, Some(3)), ("b", 8, Some(3))]);
+                            assert_eq!(units, vec![(0, 1, vec![0]), (1, 1, vec![1])]);
+                            return None;
+                        }
+
+                        assert_eq!(fields, vec![
+                            ("a", 0, Some(3)),
+                            ("b", 3, Some(5)),
+                            ("c", 32, Some(2)),
+                            ("regular", 64, None),
+                            ("d", 96, Some(1)),
+                        ]);
+
+                        assert_eq!(units, vec![
+                            (0, 1, vec![0, 1]),
+                            (4, 1, vec![2]),
+                            (12, 1, vec![4]),
+                        ]);
+                    }
+                    _ => {}
+                }
+            },
+        );
+
+        assert_generator_called![units, bitfield_gen(1)];
+    }
+
+    #[test]
+    fn test_array_fields() {
+        use ir::TypeKind;
+
+        let array_field_test_dir = DATA.clone().as_path().join("array_field_test");
+
+        let units = Generator::new().generate(
+            array_field_test_dir,
+            test_generator! {
+                array_field_gen(symbol): match symbol {
+                    ir::Symbol::Struct(decl) => {
+                        match decl.name() {
+                            "Message" => {
+                                match decl.fields()[0].ctype().kind() {
+                                    TypeKind::ConstantArray { element, length } => {
+                                        assert!(matches!(element.kind(), TypeKind::Char { .. }));
+                                        assert_eq!(*length, 32);
+                                    }
+                                    kind => panic!("unexpected kind: {:?}", kind),
+                                }
+
+                                let data = decl.flexible_array_member().unwrap();
+                                assert_eq!(data.name(), "data");
+                                assert_eq!(data.offset(), Some(36));
+                                assert!(matches!(data.ctype().kind(), TypeKind::IncompleteArray(_)));
+
+                                assert!(decl.is_dynamically_sized());
+                                assert_eq!(decl.layout().unwrap().size(), 36);
+                            }
+                            "Fixed" => {
+                                assert!(decl.flexible_array_member().is_none());
+                                assert!(!decl.is_dynamically_sized());
+                            }
+                            _ => {}
+                        }
+                    }
+                    _ => {}
+                }
+            },
+        );
+
+        assert_generator_called![units, array_field_gen(2)];
+    }
+
+    #[test]
+    fn test_packed_records() {
+        let packed_record_test_dir = DATA.clone().as_path().join("packed_record_test");
+
+        let units = Generator::new().generate(
+            packed_record_test_dir,
+            test_generator! {
+                packed_record_gen(symbol): match symbol {
+                    ir::Symbol::Struct(decl) => {
+                        let layout = decl.layout().unwrap();
+                        let field_alignments = decl.fields()
+                            .iter()
+                            .map(|field| field.alignment())
+                            .collect::<Vec<_>>();
+
+                        match decl.name() {
+                            "Packed" => {
+                                assert_eq!(layout.size(), 5);
+                                assert_eq!(layout.packing(), Some(1));
+                                assert_eq!(layout.explicit_alignment(), None);
+                            }
+                            "Pragma" => {
+                                assert_eq!(layout.size(), 6);
+                                assert_eq!(layout.packing(), Some(2));
+                                assert_eq!(layout.explicit_alignment(), None);
+                            }
+                            "Aligned" => {
+                                assert_eq!(layout.size(), 16);
+                                assert_eq!(layout.packing(), None);
+                                assert_eq!(layout.explicit_alignment(), Some(16));
+                            }
+                            "AlignedField" => {
+                                // The alignment comes from the field, not from the record.
+                                assert_eq!(layout.alignment(), 8);
+                                assert_eq!(layout.packing(), None);
+                                assert_eq!(layout.explicit_alignment(), None);
+                                assert_eq!(field_alignments, vec![None, Some(8)]);
+                                assert_eq!(decl.fields()[1].offset(), Some(8));
+                            }
+                            "Plain" => {
+                                assert_eq!(layout.packing(), None);
+                                assert_eq!(layout.explicit_alignment(), None);
+                                assert_eq!(field_alignments, vec![None, None]);
+                            }
+                            "PackedAligned" => {
+                                assert_eq!(layout.size(), 8);
+                                assert_eq!(layout.alignment(), 4);
+                                assert_eq!(layout.packing(), Some(1));
+                                assert_eq!(layout.explicit_alignment(), Some(4));
+                            }
+                            "MacroAligned" => {
+                                assert_eq!(layout.alignment(), 32);
+                                assert_eq!(layout.explicit_alignment(), Some(32));
+                            }
+                            "Flexible" => {
+                                assert_eq!(layout.alignment(), 4);
+                                assert_eq!(layout.packing(), None);
+                                assert_eq!(layout.explicit_alignment(), None);
+                            }
+                            _ => {}
+                        }
+                    }
+                    _ => {}
+                }
+            },
+        );
+
+        assert_generator_called![units, packed_record_gen(8)];
+    }
+
+    #[test]
+    fn test_source_locations() {
+        let generate_struct_test_dir = DATA.clone().as_path().join("generate_struct_test");
+        let fwd_fields_file = generate_struct_test_dir.join("fwdfields.test.c");
+        let locations = RefCell::new(vec![]);
+
+        let units = Generator::new().generate(&generate_struct_test_dir, |symbol| {
+            if let ir::Symbol::Struct(decl) = &symbol {
+                if decl.name() == "FwdFields" {
+                    locations.borrow_mut().push(symbol.location().clone());
+                }
+            }
+            None
+        });
+
+        assert!(units.iter().any(|unit| unit.path() == fwd_fields_file));
+
+        let location = |location: &ir::SourceLocation| {
+            assert_eq!(location.file(), fwd_fields_file);
+            (location.line(), location.column())
+        };
+
+        let locations = locations.into_inner();
+        assert_eq!(locations.len(), 2);
+
+        let forward = &locations[0];
+        assert_eq!(location(forward.declaration()), (3, 20));
+        assert_eq!(location(forward.definition().unwrap()), (5, 12));
+
+        let definition = &locations[1];
+        assert_eq!(location(definition.declaration()), (5, 12));
+        assert!(definition.definition().is_none());
+    }
+
+    #[test]
+    fn test_generate_union() {
+        let generate_union_test_dir = DATA.clone().as_path().join("generate_union_test");
+
+        let units = Generator::new().generate(
+            generate_union_test_dir,
+            test_generator! {
+                generate_union_gen(symbol): match symbol {
+                    ir::Symbol::Union(decl) => {
+                        match decl.name() {
+                            "Value" => check_struct![decl => union Value {
+                                i: int,
+                                d: double
+                            }],
+                            "Forward" => check_struct![decl => union Forward {}],
+                            "FwdFields" => {
+                                if decl.fields().is_empty() {
+                                    return None;
+                                } else {
+                                    check_struct![decl => union FwdFields {
+                                        some: char *
+                                    }];
+                                }
+                            },
+                            _ => {}
+                        }
+                    }
+                    _ => {}
+                }
+            },
+        );
+
+        assert_generator_called![units, generate_union_gen(3)];
+    }
+
+    #[test]
+    fn test_nested_records() {
+        use ir::{RecordKind, TypeKind};
+
+        let nested_record_test_dir = DATA.clone().as_path().join("nested_record_test");
+        let records = RefCell::new(vec![]);
+
+        Generator::new().generate(nested_record_test_dir, |symbol| {
+            match symbol {
+                ir::Symbol::Struct(decl) => records.borrow_mut().push((RecordKind::Struct, decl)),
+                ir::Symbol::Union(decl) => records.borrow_mut().push((RecordKind::Union, decl)),
+                _ => {}
+            }
+            None
+        });
+
+        let records = records.into_inner();
+        let record = |name| {
+            records
+                .iter()
+                .find(|(_, decl)| decl.name() == name)
+                .unwrap_or_else(|| panic!("{} must be generated", name))
+        };
+
+        let names = records
+            .iter()
+            .map(|(_, decl)| decl.name())
+            .collect::<Vec<_>>();
+        assert_eq!(names.len(), 5);
+
+        let (kind, inner) = record("Inner");
+        assert_eq!(*kind, RecordKind::Struct);
+        assert_eq!(inner.parent(), Some("Outer"));
+
+        let (kind, outer_u) = record("Outer_u");
+        assert_eq!(*kind, RecordKind::Union);
+        assert_eq!(outer_u.parent(), Some("Outer"));
+
+        let (kind, outer_anon0) = record("Outer_anon0");
+        assert_eq!(*kind, RecordKind::Union);
+        assert_eq!(outer_anon0.parent(), Some("Outer"));
+        let anon_fields = outer_anon0.fields().iter().map(|field| field.name());
+        assert_eq!(anon_fields.collect::<Vec<_>>(), vec!["c", "s"]);
+
+        let (_, outer) = record("Outer");
+        assert_eq!(outer.parent(), None);
+
+        let fields = outer
+            .fields()
+            .iter()
+            .map(|field| (field.name(), field.ctype().kind().clone()))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            fields,
+            vec![
+                (
+                    "in",
+                    TypeKind::Record {
+                        kind: RecordKind::Struct,
+                        name: String::from("Inner")
+                    }
+                ),
+                (
+                    "u",
+                    TypeKind::Record {
+                        kind: RecordKind::Union,
+                        name: String::from("Outer_u")
+                    }
+                ),
+                (
+                    "anon0",
+                    TypeKind::Record {
+                        kind: RecordKind::Union,
+                        name: String::from("Outer_anon0")
+                    }
+                ),
+            ]
+        );
+
+        let (_, point) = record("point_t");
+        assert_eq!(point.parent(), None);
+        assert_eq!(point.fields()[0].name(), "x");
+    }
+
+    #[test]
+    fn test_tagged_anonymous_member() {
+        let ms_extensions_test_dir = DATA.clone().as_path().join("ms_extensions_test");
+
+        let units = Generator::new().c_flag("-fms-extensions").generate(
+            ms_extensions_test_dir,
+            test_generator! {
+                tagged_member_gen(symbol): match symbol {
+                    ir::Symbol::Struct(decl) if decl.name() == "MsOuter" => {
+                        let fields = decl.fields()
+                            .iter()
+                            .map(|field| field.name())
+                            .collect::<Vec<_>>();
+
+                        // The tagged member is named after its record, which is not nested.
+                        assert_eq!(fields, vec!["a", "Tagged", "anon1"]);
+                    }
+                    _ => return None,
+                }
+            },
+        );
+
+        assert_generator_called![units, tagged_member_gen(1)];
+    }
+
+    #[test]
+    fn test_generate_var() {
+        let generate_var_test_dir = DATA.clone().as_path().join("generate_var_test");
+
+        let units = Generator::new().generate(
+            generate_var_test_dir,
+            test_generator! {
+                generate_var_gen(symbol): match symbol {
+                    ir::Symbol::Variable(var) => check_var_symbol![var => test_var: const char *],
+                    _ => {}
+                }
+            },
+        );
 
         assert_generator_called![units, generate_var_gen(1)];
     }
 
+    #[test]
+    fn test_var_storage() {
+        use ir::DefinitionKind;
+
+        let var_storage_test_dir = DATA.clone().as_path().join("var_storage_test");
+        let vars = RefCell::new(HashMap::new());
+
+        Generator::new().generate(var_storage_test_dir, |symbol| {
+            if let ir::Symbol::Variable(var) = symbol {
+                vars.borrow_mut().insert(String::from(var.name()), var);
+            }
+            None
+        });
+
+        let vars = vars.into_inner();
+        let var = |name: &str| {
+            vars.get(name)
+                .unwrap_or_else(|| panic!("{} must be generated", name))
+        };
+
+        assert!(var("const_var").is_const());
+        assert!(var("const_chars").is_const());
+        assert!(!var("mutable_var").is_const());
+
+        assert!(var("tls_var").is_thread_local());
+        assert!(!var("mutable_var").is_thread_local());
+
+        assert_eq!(var("mutable_var").definition(), DefinitionKind::Definition);
+        assert_eq!(var("extern_var").definition(), DefinitionKind::Declaration);
+        assert!(!var("extern_var").is_defined());
+        assert_eq!(var("tentative_var").definition(), DefinitionKind::Tentative);
+        assert!(var("tentative_var").is_defined());
+        assert_eq!(
+            var("defined_later_var").definition(),
+            DefinitionKind::Definition
+        );
+    }
+
+    #[test]
+    fn test_generate_enum() {
+        let generate_enum_test_dir = DATA.clone().as_path().join("generate_enum_test");
+
+        let units = Generator::new().generate(
+            generate_enum_test_dir,
+            test_generator! {
+                generate_enum_gen(symbol): match symbol {
+                    ir::Symbol::Enum(decl) => {
+                        let constants = decl.constants()
+                            .iter()
+                            .map(|constant| (constant.name(), constant.value()))
+                            .collect::<Vec<_>>();
+
+                        match decl.name() {
+                            Some("Color") => {
+                                assert_eq!(decl.ctype().display_name(), "enum Color");
+                                assert_eq!(constants, vec![
+                                    ("RED", ir::EnumValue::Signed(0)),
+                                    ("GREEN", ir::EnumValue::Signed(5)),
+                                    ("BLUE", ir::EnumValue::Signed(-1)),
+                                ]);
+                            }
+                            Some("Big") => {
+                                assert_eq!(decl.underlying_type().display_name(), "unsigned int");
+                                assert_eq!(constants, vec![
+                                    ("BIG_MAX", ir::EnumValue::Unsigned(0xFFFF_FFFF)),
+                                ]);
+                            }
+                            None => assert_eq!(constants, vec![
+                                ("FLAG_A", ir::EnumValue::Signed(1)),
+                                ("FLAG_B", ir::EnumValue::Signed(2)),
+                            ]),
+                            Some(name) => panic!("unexpected enum: {}", name),
+                        }
+                    }
+                    _ => {}
+                }
+            },
+        );
+
+        assert_generator_called![units, generate_enum_gen(3)];
+    }
+
+    #[test]
+    fn test_generate_typedef() {
+        use ir::TypeKind;
+
+        let generate_typedef_test_dir = DATA.clone().as_path().join("generate_typedef_test");
+
+        let units = Generator::new().generate(
+            generate_typedef_test_dir,
+            test_generator! {
+                generate_typedef_gen(symbol): match symbol {
+                    ir::Symbol::Typedef(decl) => {
+                        let underlying = decl.underlying_type();
+
+                        match decl.name() {
+                            "int_t" => assert_eq!(underlying.display_name(), "int"),
+                            "chain_t" => {
+                                match underlying.kind() {
+                                    TypeKind::Typedef { name, underlying } => {
+                                        assert_eq!(name, "base_t");
+                                        assert_eq!(underlying.display_name(), "unsigned long");
+                                    }
+                                    kind => panic!("unexpected kind: {:?}", kind),
+                                }
+                                assert_eq!(underlying.canonical().display_name(), "unsigned long");
+                            }
+                            "cb_t" => match underlying.kind() {
+                                TypeKind::Pointer(pointee) => {
+                                    assert!(matches!(pointee.kind(), TypeKind::Function(_)));
+                                }
+                                kind => panic!("unexpected kind: {:?}", kind),
+                            },
+                            "foo_t" => assert!(matches!(
+                                underlying.canonical().kind(),
+                                TypeKind::Record { .. }
+                            )),
+                            _ => return None,
+                        }
+                    }
+                    ir::Symbol::Function(signature) => {
+                        let handle = signature.parameters()[0].ctype();
+
+                        assert_eq!(handle.display_name(), "handle_t");
+                        assert_eq!(handle.canonical().display_name(), "unsigned int");
+                        assert_eq!(handle.canonical().canonical(), handle.canonical());
+                    }
+                    _ => return None,
+                }
+            },
+        );
+
+        assert_generator_called![units, generate_typedef_gen(5)];
+    }
+
+    #[test]
+    fn test_generate_constant() {
+        use ir::ConstantValue;
+        use std::ffi::CString;
+
+        let generate_constant_test_dir = DATA.clone().as_path().join("generate_constant_test");
+
+        let units = Generator::new().generate(
+            generate_constant_test_dir,
+            test_generator! {
+                generate_constant_gen(symbol): match symbol {
+                    ir::Symbol::Constant(constant) => {
+                        let value = constant.value().clone();
+
+                        match constant.name() {
+                            "FOO_MAX" => {
+                                assert_eq!(value, ConstantValue::Signed(64));
+                                assert_eq!(constant.ctype().display_name(), "int");
+                            }
+                            "FOO_NAME" => {
+                                assert_eq!(value, ConstantValue::String(CString::new("x").unwrap()));
+                            }
+                            "FOO_PI" => {
+                                assert_eq!(value, ConstantValue::Float(3.5));
+                                assert_eq!(constant.ctype().display_name(), "double");
+                            }
+                            "FOO_SEP" => assert_eq!(value, ConstantValue::Char(b',')),
+                            "FOO_FLAGS" => {
+                                assert_eq!(value, ConstantValue::Unsigned(16));
+                                assert_eq!(constant.ctype().display_name(), "unsigned int");
+                            }
+                            "FOO_NEG" => {
+                                assert_eq!(value, ConstantValue::Signed(-2));
+                                assert_eq!(constant.ctype().display_name(), "long");
+                            }
+                            name => {
+                                assert_eq!(name, "FOO_BIT");
+                                return None;
+                            }
+                        }
+                    }
+                    _ => {}
+                }
+            },
+        );
+
+        assert_generator_called![units, generate_constant_gen(6)];
+    }
+
+    #[test]
+    fn test_export_policy() {
+        let exported = |policy| {
+            let export_policy_test_dir = DATA.clone().as_path().join("export_policy_test");
+            let names = RefCell::new(vec![]);
+
+            Generator::new()
+                .export_policy(policy)
+                .generate(export_policy_test_dir, |symbol| {
+                    match symbol {
+                        ir::Symbol::Function(signature) => {
+                            names.borrow_mut().push(String::from(signature.name()))
+                        }
+                        ir::Symbol::Variable(var) => {
+                            names.borrow_mut().push(String::from(var.name()))
+                        }
+                        _ => {}
+                    }
+                    None
+                });
+
+            let mut names = names.into_inner();
+            names.sort();
+            names
+        };
+
+        assert_eq!(
+            exported(ExportPolicy::ExternalLinkage),
+            vec![
+                "annotated_fn",
+                "header_fn",
+                "marked_fn",
+                "plain_fn",
+                "plain_var"
+            ]
+        );
+        assert_eq!(
+            exported(ExportPolicy::DefaultVisibility),
+            exported(ExportPolicy::ExternalLinkage)
+        );
+        assert_eq!(
+            exported(ExportPolicy::DeclaredIn(vec![PathBuf::from("exports.h")])),
+            vec!["header_fn"]
+        );
+        assert_eq!(
+            exported(ExportPolicy::Annotation(String::from("export"))),
+            vec!["annotated_fn"]
+        );
+        assert_eq!(
+            exported(ExportPolicy::Macro(String::from("MARKED"))),
+            vec!["marked_fn"]
+        );
+        assert_eq!(
+            exported(ExportPolicy::Custom(Box::new(|symbol| {
+                matches!(symbol, ir::Symbol::Variable(_))
+            }))),
+            vec!["plain_var"]
+        );
+
+        let exported_constants = |policy| {
+            let export_policy_test_dir = DATA.clone().as_path().join("export_policy_test");
+            let names = RefCell::new(vec![]);
+
+            Generator::new()
+                .export_policy(policy)
+                .generate(export_policy_test_dir, |symbol| {
+                    if let ir::Symbol::Constant(constant) = symbol {
+                        names.borrow_mut().push(String::from(constant.name()));
+                    }
+                    None
+                });
+
+            let mut names = names.into_inner();
+            names.sort();
+            names
+        };
+
+        assert_eq!(
+            exported_constants(ExportPolicy::ExternalLinkage),
+            vec!["LOCAL_CONST", "POLICY_CONST"]
+        );
+        assert_eq!(
+            exported_constants(ExportPolicy::DeclaredIn(vec![PathBuf::from("exports.h")])),
+            vec!["POLICY_CONST"]
+        );
+        assert!(exported_constants(ExportPolicy::Custom(Box::new(|symbol| {
+            matches!(symbol, ir::Symbol::Variable(_))
+        })))
+        .is_empty());
+
+        // A constant rejected by the policy doesn't take the name of a later variable.
+        let constant_policy_test_dir = DATA.clone().as_path().join("constant_policy_test");
+        let units = Generator::new()
+            .export_policy(ExportPolicy::Custom(Box::new(|symbol| {
+                matches!(symbol, ir::Symbol::Variable(_))
+            })))
+            .generate(
+                constant_policy_test_dir,
+                test_generator! {
+                    constant_policy_gen(symbol): match symbol {
+                        ir::Symbol::Variable(var) => assert_eq!(var.name(), "shared_name"),
+                        _ => return None,
+                    }
+                },
+            );
+
+        assert_generator_called![units, constant_policy_gen(1)];
+    }
+
+    #[test]
+    fn test_export_marker() {
+        let exported = |marker| {
+            let export_marker_test_dir = DATA.clone().as_path().join("export_marker_test");
+            let names = RefCell::new(vec![]);
+
+            Generator::new()
+                .export_policy(ExportPolicy::Macro(String::from(marker)))
+                .generate(export_marker_test_dir, |symbol| {
+                    match symbol {
+                        ir::Symbol::Function(signature) => {
+                            names.borrow_mut().push(String::from(signature.name()))
+                        }
+                        ir::Symbol::Variable(var) => {
+                            names.borrow_mut().push(String::from(var.name()))
+                        }
+                        _ => {}
+                    }
+                    None
+                });
+
+            let mut names = names.into_inner();
+            names.sort();
+            names
+        };
+
+        assert_eq!(
+            exported("MYLIB_EXPORT"),
+            vec![
+                "first_marked",
+                "inner_marked",
+                "marked_var",
+                "next_line_marked"
+            ]
+        );
+        assert_eq!(exported("MYLIB_API"), vec!["api_fn"]);
+        assert!(exported("MYLIB_UNUSED").is_empty());
+    }
+
+    #[test]
+    fn test_attributes() {
+        use ir::Attribute;
+
+        let attribute_test_dir = DATA.clone().as_path().join("attribute_test");
+        let attributes = RefCell::new(HashMap::new());
+
+        Generator::new().generate(attribute_test_dir, |symbol| {
+            let (name, symbol_attributes) = match &symbol {
+                ir::Symbol::Function(signature) => (signature.name(), signature.attributes()),
+                ir::Symbol::Variable(var) => (var.name(), var.attributes()),
+                ir::Symbol::Struct(decl) => (decl.name(), decl.attributes()),
+                _ => return None,
+            };
+
+            attributes
+                .borrow_mut()
+                .insert(String::from(name), symbol_attributes.clone());
+            None
+        });
+
+        let attributes = attributes.into_inner();
+        let attributes_of = |name: &str| {
+            attributes
+                .get(name)
+                .unwrap_or_else(|| panic!("{} must be generated", name))
+        };
+
+        assert_eq!(
+            attributes_of("old_api"),
+            &vec![Attribute::Deprecated(Some(String::from(
+                "Use new_api instead"
+            )))]
+        );
+        assert_eq!(attributes_of("fatal"), &vec![Attribute::NoReturn]);
+
+        // The order of attributes in one declaration is not guaranteed.
+        let copy = attributes_of("copy");
+        assert_eq!(copy.len(), 2);
+        assert!(copy.contains(&Attribute::WarnUnusedResult));
+        assert!(copy.contains(&Attribute::NonNull(vec![1, 2])));
+
+        let macro_copy = attributes_of("macro_copy");
+        assert_eq!(macro_copy.len(), 2);
+        assert!(macro_copy.contains(&Attribute::WarnUnusedResult));
+        assert!(macro_copy.contains(&Attribute::NonNull(vec![1, 2])));
+
+        let macro_checked = attributes_of("macro_checked");
+        assert_eq!(macro_checked.len(), 2);
+        assert!(macro_checked.contains(&Attribute::WarnUnusedResult));
+        assert!(macro_checked.contains(&Attribute::NonNull(vec![1])));
+
+        assert_eq!(attributes_of("gnu_fatal"), &vec![Attribute::NoReturn]);
+        assert!(attributes_of("run_handler").is_empty());
+        assert!(attributes_of("count_noreturns").is_empty());
+
+        let allocate = attributes_of("allocate");
+        assert_eq!(allocate.len(), 3);
+        assert!(allocate.contains(&Attribute::ReturnsNonNull));
+        assert!(allocate.contains(&Attribute::Malloc));
+        assert!(allocate.contains(&Attribute::AllocSize(vec![1])));
+
+        assert_eq!(
+            attributes_of("print"),
+            &vec![Attribute::Format {
+                archetype: String::from("printf"),
+                string_index: 1,
+                first_to_check: 2,
+            }]
+        );
+        assert_eq!(attributes_of("length"), &vec![Attribute::Pure]);
+        assert_eq!(attributes_of("square"), &vec![Attribute::Const]);
+        assert_eq!(attributes_of("old_var"), &vec![Attribute::Deprecated(None)]);
+        assert_eq!(
+            attributes_of("OldPoint"),
+            &vec![Attribute::Deprecated(Some(String::from(
+                "Use Point instead"
+            )))]
+        );
+    }
+
+    #[test]
+    fn test_doc_comments() {
+        let doc_comment_test_dir = DATA.clone().as_path().join("doc_comment_test");
+        let symbols = RefCell::new(vec![]);
+
+        Generator::new().generate(doc_comment_test_dir, |symbol| {
+            symbols.borrow_mut().push(symbol);
+            None
+        });
+
+        for symbol in symbols.into_inner() {
+            match symbol {
+                ir::Symbol::Function(signature) => {
+                    let comment = signature.comment().unwrap();
+                    assert!(comment.raw().starts_with("/**"));
+                    assert_eq!(comment.brief(), Some("Sums two integers."));
+                    assert_eq!(
+                        comment.details(),
+                        &vec!["The result wraps around on overflow."]
+                    );
+                    assert_eq!(comment.param("a").unwrap().description(), "The first term.");
+                    assert_eq!(
+                        comment.param("b").unwrap().description(),
+                        "The second term."
+                    );
+                    assert_eq!(comment.returns(), Some("The sum of @p a and @p b."));
+                    assert_eq!(comment.deprecated(), Some("Use checked_sum instead."));
+                    assert_eq!(comment.see(), &vec!["checked_sum"]);
+
+                    let parameter = &signature.parameters()[1];
+                    assert!(parameter.comment().is_none());
+                    let param_comment = parameter.param_comment().unwrap();
+                    assert_eq!(param_comment.name(), "b");
+                    assert_eq!(param_comment.description(), "The second term.");
+                }
+                ir::Symbol::Struct(decl) => {
+                    assert_eq!(decl.comment().unwrap().brief(), Some("A point on a plane."));
+
+                    let fields = decl.fields();
+                    let field_brief = |index: usize| fields[index].comment().unwrap().brief();
+                    assert_eq!(field_brief(0), Some("The abscissa."));
+                    assert_eq!(field_brief(1), Some("The ordinate."));
+                }
+                ir::Symbol::Enum(decl) => {
+                    let comment = decl.comment().unwrap();
+                    assert_eq!(comment.brief(), Some("Primary colors."));
+                    assert_eq!(comment.details(), &vec!["Used to draw points."]);
+
+                    let constants = decl.constants();
+                    let red_comment = constants[0].comment().unwrap();
+                    assert_eq!(red_comment.brief(), Some("The red color."));
+                    assert!(constants[1].comment().is_none());
+                }
+                ir::Symbol::Constant(constant) if constant.name() == "MAX_POINTS" => {
+                    let comment = constant.comment().unwrap();
+                    assert_eq!(comment.raw(), "/// The maximal number of points.");
+                    assert_eq!(comment.brief(), Some("The maximal number of points."));
+                }
+                _ => {}
+            }
+        }
+    }
+
+    #[test]
+    fn test_ctype_kinds() {
+        use ir::TypeKind;
+
+        let ctype_test_dir = DATA.clone().as_path().join("ctype_test");
+
+        let units = Generator::new().generate(
+            ctype_test_dir,
+            test_generator! {
+                ctype_gen(symbol): match symbol {
+                    ir::Symbol::Struct(decl) => {
+                        let field = |name| decl.fields()
+                            .iter()
+                            .find(|field| field.name() == name)
+                            .unwrap()
+                            .ctype();
+
+                        assert_eq!(
+                            *field("u8").kind(),
+                            TypeKind::Integer { width: 8, signed: false }
+                        );
+                        assert_eq!(
+                            *field("i64").kind(),
+                            TypeKind::Integer { width: 64, signed: true }
+                        );
+                        assert!(matches!(field("c").kind(), TypeKind::Char { .. }));
+                        assert_eq!(*field("f").kind(), TypeKind::Float { width: 32 });
+
+                        match field("const_str").kind() {
+                            TypeKind::Pointer(pointee) => {
+                                assert!(pointee.is_const());
+                                assert!(matches!(pointee.kind(), TypeKind::Char { .. }));
+                            }
+                            kind => panic!("unexpected kind: {:?}", kind),
+                        }
+
+                        let const_ptr = field("const_ptr");
+                        assert!(const_ptr.is_const());
+                        assert!(matches!(const_ptr.kind(), TypeKind::Pointer(_)));
+
+                        let restrict_ptr = field("restrict_ptr");
+                        assert!(restrict_ptr.is_restrict());
+                        match restrict_ptr.kind() {
+                            TypeKind::Pointer(pointee) => assert!(pointee.is_volatile()),
+                            kind => panic!("unexpected kind: {:?}", kind),
+                        }
+
+                        match field("fixed").kind() {
+                            TypeKind::ConstantArray { element, length } => {
+                                assert_eq!(*length, 4);
+                                assert_eq!(element.display_name(), "int");
+                            }
+                            kind => panic!("unexpected kind: {:?}", kind),
+                        }
+
+                        match field("callback").kind() {
+                            TypeKind::Pointer(pointee) => match pointee.kind() {
+                                TypeKind::Function(fn_type) => {
+                                    assert_eq!(fn_type.result_type().display_name(), "int");
+                                    assert_eq!(fn_type.parameter_types().len(), 2);
+                                    assert!(matches!(
+                                        fn_type.parameter_types()[0].kind(),
+                                        TypeKind::Pointer(_)
+                                    ));
+                                }
+                                kind => panic!("unexpected kind: {:?}", kind),
+                            },
+                            kind => panic!("unexpected kind: {:?}", kind),
+                        }
+
+                        match field("next").kind() {
+                            TypeKind::Pointer(pointee) => assert_eq!(
+                                *pointee.kind(),
+                                TypeKind::Record {
+                                    kind: ir::RecordKind::Struct,
+                                    name: String::from("Types")
+                                }
+                            ),
+                            kind => panic!("unexpected kind: {:?}", kind),
+                        }
+
+                        assert_eq!(*field("color").kind(), TypeKind::Enum(String::from("Color")));
+
+                        match field("alias").kind() {
+                            TypeKind::Typedef { name, underlying } => {
+                                assert_eq!(name, "size_alias_t");
+                                assert!(matches!(
+                                    underlying.kind(),
+                                    TypeKind::Integer { signed: false, .. }
+                                ));
+                            }
+                            kind => panic!("unexpected kind: {:?}", kind),
+                        }
+                    }
+                    _ => return None,
+                }
+            },
+        );
+
+        assert_generator_called![units, ctype_gen(1)];
+    }
+
+    #[test]
+    fn test_symbols_outlive_generator() {
+        fn assert_send<T: Send>(_: &T) {}
+
+        let generate_fn_test_dir = DATA.clone().as_path().join("generate_fn_test");
+        let symbols = RefCell::new(vec![]);
+
+        let units = Generator::new().generate(generate_fn_test_dir, |symbol| {
+            symbols.borrow_mut().push(symbol);
+            None
+        });
+
+        assert!(!units.is_empty());
+
+        let symbols = symbols.into_inner();
+        assert_send(&symbols);
+        // Only the functions are collected, the builtin typedefs have no file.
+        assert_eq!(symbols.len(), 4);
+        assert!(symbols
+            .iter()
+            .all(|symbol| matches!(symbol, ir::Symbol::Function(_))
+                && symbol.location().declaration().file().is_file()));
+
+        let fn2 = symbols
+            .iter()
+            .find_map(|symbol| match symbol {
+                ir::Symbol::Function(signature) if signature.name() == "fn2" => Some(signature),
+                _ => None,
+            })
+            .expect("fn2 must be collected");
+
+        assert_eq!(fn2.result_type().display_name(), "double");
+        assert_eq!(fn2.parameters()[1].ctype().display_name(), "const char *");
+        assert_eq!(fn2.clone(), *fn2);
+    }
+
+    #[test]
+    fn test_recursive_units() {
+        let recursive_test_dir = DATA.clone().as_path().join("recursive_test");
+
+        let units = Generator::new().generate(
+            &recursive_test_dir,
+            test_generator! {
+                recursive_gen(symbol): match symbol {
+                    ir::Symbol::Function(_) => {}
+                    _ => return None,
+                }
+            },
+        );
+
+        assert_generator_called![units, recursive_gen(3)];
+
+        let mut relative_paths = units
+            .iter()
+            .map(|unit| unit.relative_path().to_path_buf())
+            .collect::<Vec<_>>();
+        relative_paths.sort();
+
+        assert_eq!(
+            relative_paths,
+            vec![
+                Path::new("sub").join("deeper").join("deep.test.c"),
+                Path::new("sub").join("nested.test.c"),
+                PathBuf::from("top.test.c"),
+            ]
+        );
+
+        for unit in &units {
+            assert_eq!(unit.path(), recursive_test_dir.join(unit.relative_path()));
+        }
+    }
+
+    #[test]
+    fn test_symbol_ownership() {
+        let ownership_test_dir = DATA.clone().as_path().join("ownership_test");
+
+        let units = Generator::new().generate(ownership_test_dir, |symbol| match symbol {
+            ir::Symbol::Function(signature) => {
+                let name = format_ident!("{}", signature.name());
+                Some(quote!(#name))
+            }
+            _ => None,
+        });
+
+        let units = units
+            .iter()
+            .map(|unit| (unit.name(), unit.tokens().to_string()))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            units,
+            vec![
+                ("a.test.c", String::from("shared_fn a_fn")),
+                ("b.test.c", String::from("b_fn")),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_source_file_selection() {
+        let relative_paths = |units: Vec<ir::TranslationUnit>| {
+            let mut paths = units
+                .iter()
+                .map(|unit| unit.relative_path().to_path_buf())
+                .collect::<Vec<_>>();
+            paths.sort();
+            paths
+        };
+
+        let recursive_test_dir = DATA.clone().as_path().join("recursive_test");
+
+        let units = Generator::new()
+            .exclude_glob("sub/**")
+            .generate(&recursive_test_dir, |_| None);
+        assert_eq!(relative_paths(units), vec![PathBuf::from("top.test.c")]);
+
+        let units = Generator::new()
+            .include_glob("sub/*.c")
+            .generate(&recursive_test_dir, |_| None);
+        assert_eq!(
+            relative_paths(units),
+            vec![Path::new("sub").join("nested.test.c")]
+        );
+
+        let export_policy_test_dir = DATA.clone().as_path().join("export_policy_test");
+        let header_fns = RefCell::new(vec![]);
+
+        let units =
+            Generator::new()
+                .extensions(&["h"])
+                .generate(&export_policy_test_dir, |symbol| {
+                    if let ir::Symbol::Function(signature) = symbol {
+                        header_fns.borrow_mut().push(String::from(signature.name()));
+                    }
+                    None
+                });
+        assert_eq!(relative_paths(units), vec![PathBuf::from("exports.h")]);
+        assert_eq!(header_fns.into_inner(), vec!["header_fn"]);
+
+        let files = [
+            export_policy_test_dir.join("exports.h"),
+            recursive_test_dir.join("top.test.c"),
+        ];
+        let units = Generator::new().generate_files(&files, |_| None);
+        assert_eq!(relative_paths(units), files.to_vec());
+    }
+
+    #[test]
+    fn test_compilation_database() {
+        let database_test_dir = DATA.clone().as_path().join("compilation_database_test");
+        let database_dir = env::temp_dir().join(format!(
+            "tebindgen_compilation_database_test_{}",
+            std::process::id()
+        ));
+        fs::create_dir_all(&database_dir).unwrap();
+
+        // The paths of the database must be absolute, so it is written at runtime.
+        let database = format!(
+            r#"[{{
+                "directory": {:?},
+                "file": "src/db.test.c",
+                "arguments": ["cc", "-c", "-Iinclude", "-DDB_RETURN_T=double", "-o", "db.o", "src/db.test.c"]
+            }}]"#,
+            database_test_dir.to_str().unwrap()
+        );
+        fs::write(database_dir.join("compile_commands.json"), database).unwrap();
+
+        let units = Generator::new().generate_from_compilation_database(
+            &database_dir,
+            &database_test_dir,
+            test_generator! {
+                compilation_database_gen(symbol): match symbol {
+                    ir::Symbol::Function(signature) => match signature.name() {
+                        "db_fn" => {
+                            assert_eq!(signature.ctype().display_name(), "double (void)");
+                        }
+                        _ => return None,
+                    },
+                    _ => return None,
+                }
+            },
+        );
+
+        assert_generator_called![units, compilation_database_gen(1)];
+        assert_eq!(units[0].relative_path(), Path::new("src").join("db.test.c"));
+
+        fs::remove_dir_all(&database_dir).unwrap();
+    }
+
+    #[test]
+    fn test_out_of_source_compilation_database() {
+        let database_test_dir = DATA.clone().as_path().join("compilation_database_test");
+        let build_dir = env::temp_dir()
+            .join(format!(
+                "tebindgen_out_of_source_test_{}",
+                std::process::id()
+            ))
+            .join("build");
+        fs::create_dir_all(&build_dir).unwrap();
+
+        // The build directory is outside of the sources, as with CMake.
+        let database = format!(
+            r#"[{{
+                "directory": {0:?},
+                "file": {1:?},
+                "arguments": ["cc", "-c", "-I{2}", "-DDB_RETURN_T=float", "-o", "db.o", {1:?}]
+            }}]"#,
+            build_dir.to_str().unwrap(),
+            database_test_dir
+                .join("src")
+                .join("db.test.c")
+                .to_str()
+                .unwrap(),
+            database_test_dir.join("include").to_str().unwrap()
+        );
+        fs::write(build_dir.join("compile_commands.json"), database).unwrap();
+
+        let units = Generator::new()
+            .include_glob("src/*")
+            .generate_from_compilation_database(
+                &build_dir,
+                &database_test_dir,
+                test_generator! {
+                    out_of_source_gen(symbol): match symbol {
+                        ir::Symbol::Function(signature) => match signature.name() {
+                            "db_fn" => {
+                                assert_eq!(signature.ctype().display_name(), "float (void)");
+                            }
+                            _ => return None,
+                        },
+                        _ => return None,
+                    }
+                },
+            );
+
+        assert_generator_called![units, out_of_source_gen(1)];
+        assert_eq!(units[0].relative_path(), Path::new("src").join("db.test.c"));
+
+        fs::remove_dir_all(build_dir.parent().unwrap()).unwrap();
+    }
+
     #[test]
     fn test_include_flag() {
         let include_test_dir = DATA.clone().as_path().join("include_test");
@@ -622,6 +3332,43 @@ mod tests {
         assert!(generator.arguments.is_empty());
     }
 
+    #[test]
+    fn test_file_c_flags() {
+        let file_arguments_test_dir = DATA.clone().as_path().join("file_arguments_test");
+
+        let units = Generator::new()
+            .define_value("BASE_T", "int")
+            .file_c_flags("legacy/**", &["-DLEGACY_T=double"])
+            .file_c_flags_callback(|path| {
+                if path == Path::new("a.test.c") {
+                    vec![String::from("-DCALLBACK_T=char")]
+                } else {
+                    vec![]
+                }
+            })
+            .generate(
+                file_arguments_test_dir,
+                test_generator! {
+                    file_arguments_gen(symbol): match symbol {
+                        ir::Symbol::Function(signature) => match signature.name() {
+                            "a_fn" => check_fn_symbol![signature => {
+                                name: a_fn,
+                                ctype: int(x: char)
+                            }],
+                            "b_fn" => check_fn_symbol![signature => {
+                                name: b_fn,
+                                ctype: int(x: double)
+                            }],
+                            name => panic!("unexpected function: {}", name),
+                        },
+                        _ => return None,
+                    }
+                },
+            );
+
+        assert_generator_called![units, file_arguments_gen(2)];
+    }
+
     #[test]
     fn test_define_value_flag() {
         let define_value_test_dir = DATA.clone().as_path().join("define_value_test");
@@ -644,4 +3391,32 @@ mod tests {
 
         assert_generator_called![units, define_value_gen(1)];
     }
+
+    #[test]
+    fn test_target() {
+        let exported = |triple| {
+            let target_test_dir = DATA.clone().as_path().join("target_test");
+            let names = RefCell::new(vec![]);
+
+            Generator::new()
+                .target(triple)
+                .generate(target_test_dir, |symbol| {
+                    if let ir::Symbol::Function(signature) = symbol {
+                        names.borrow_mut().push(String::from(signature.name()));
+                    }
+                    None
+                });
+
+            let mut names = names.into_inner();
+            names.sort();
+            names
+        };
+
+        assert_eq!(exported("x86_64-pc-windows-msvc"), vec!["exported_fn"]);
+        assert_eq!(exported("x86_64-w64-mingw32"), vec!["exported_fn"]);
+        assert_eq!(
+            exported("x86_64-unknown-linux-gnu"),
+            vec!["exported_fn", "plain_fn"]
+        );
+    }
 }